@@ -0,0 +1,97 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+pub use crate::{
+    access_control::*,
+    traits::access_control::extensions::enumerable::*,
+};
+use brush::traits::AccountId;
+pub use derive::AccessControlEnumerableStorage;
+use ink_storage::Mapping;
+
+pub const STORAGE_KEY: [u8; 32] = ink_lang::blake2x256!("brush::AccessControlEnumerableData");
+
+#[derive(Default, Debug)]
+#[brush::storage(STORAGE_KEY)]
+pub struct AccessControlEnumerableData {
+    /// Mapping from role's index to the member.
+    pub role_members: Mapping<(RoleType, u32), AccountId>,
+    /// Mapping from the member of the role to its index.
+    pub member_indexes: Mapping<(RoleType, AccountId), u32>,
+    /// Mapping from role to the number of its members.
+    pub member_counts: Mapping<RoleType, u32>,
+    pub _reserved: Option<()>,
+}
+
+pub trait AccessControlEnumerableStorage: AccessControlStorage + ::brush::traits::InkStorage {
+    fn get(&self) -> &AccessControlEnumerableData;
+    fn get_mut(&mut self) -> &mut AccessControlEnumerableData;
+}
+
+impl<T: AccessControlEnumerableStorage> AccessControlMembers for T {
+    default fn _add_member(&mut self, role: RoleType, account: AccountId) {
+        AccessControlStorage::get_mut(self).members.insert((&role, &account), &());
+
+        let data = AccessControlEnumerableStorage::get_mut(self);
+        if data.member_indexes.get((&role, &account)).is_some() {
+            return
+        }
+        let index = data.member_counts.get(&role).unwrap_or(0);
+        data.role_members.insert((&role, &index), &account);
+        data.member_indexes.insert((&role, &account), &index);
+        data.member_counts.insert(&role, &(index + 1));
+    }
+
+    default fn _remove_member(&mut self, role: RoleType, account: AccountId) {
+        AccessControlStorage::get_mut(self).members.remove((&role, &account));
+
+        let data = AccessControlEnumerableStorage::get_mut(self);
+        let index = match data.member_indexes.get((&role, &account)) {
+            Some(index) => index,
+            None => return,
+        };
+        let last_index = data.member_counts.get(&role).unwrap_or(1) - 1;
+
+        if last_index != index {
+            if let Some(last_member) = data.role_members.get((&role, &last_index)) {
+                data.role_members.insert((&role, &index), &last_member);
+                data.member_indexes.insert((&role, &last_member), &index);
+            }
+        }
+
+        data.role_members.remove((&role, &last_index));
+        data.member_indexes.remove((&role, &account));
+        data.member_counts.insert(&role, &last_index);
+    }
+}
+
+impl<T: AccessControlEnumerableStorage> AccessControlEnumerable for T {
+    default fn get_role_member(&self, role: RoleType, index: u32) -> Option<AccountId> {
+        AccessControlEnumerableStorage::get(self).role_members.get((&role, &index))
+    }
+
+    default fn get_role_member_count(&self, role: RoleType) -> u32 {
+        AccessControlEnumerableStorage::get(self)
+            .member_counts
+            .get(&role)
+            .unwrap_or(0)
+    }
+}
@@ -24,11 +24,18 @@ use brush::{
     declare_storage_trait,
     modifier_definition,
     modifiers,
-    traits::AccountId,
+    traits::{
+        AccountId,
+        Timestamp,
+    },
 };
 pub use derive::AccessControlStorage;
 use ink_storage::Mapping;
 
+pub mod extensions {
+    pub mod enumerable;
+}
+
 pub const STORAGE_KEY: [u8; 32] = ink_lang::blake2x256!("brush::AccessControlData");
 
 #[derive(Default, Debug)]
@@ -36,6 +43,7 @@ pub const STORAGE_KEY: [u8; 32] = ink_lang::blake2x256!("brush::AccessControlDat
 pub struct AccessControlData {
     pub admin_roles: Mapping<RoleType, RoleType>,
     pub members: Mapping<(RoleType, AccountId), ()>,
+    pub expirations: Mapping<(RoleType, AccountId), Timestamp>,
     pub _reserved: Option<()>,
 }
 
@@ -68,17 +76,41 @@ impl<T: AccessControlStorage> AccessControl for T {
 
     #[modifiers(only_role(get_role_admin(self, &role)))]
     default fn grant_role(&mut self, role: RoleType, account: AccountId) -> Result<(), AccessControlError> {
-        if has_role(self, &role, &account) {
+        if has_role(self, &role, &account) && get_role_expiry(self, &role, &account).is_none() {
             return Err(AccessControlError::RoleRedundant)
         }
-        self.get_mut().members.insert((&role, &account), &());
+        self._add_member(role, account);
+        self.get_mut().expirations.remove((&role, &account));
         self._emit_role_granted(role, account, Some(Self::env().caller()));
         Ok(())
     }
 
+    #[modifiers(only_role(get_role_admin(self, &role)))]
+    default fn grant_role_until(
+        &mut self,
+        role: RoleType,
+        account: AccountId,
+        expiry: Timestamp,
+    ) -> Result<(), AccessControlError> {
+        if expiry <= Self::env().block_timestamp() {
+            return Err(AccessControlError::InvalidExpiry)
+        }
+        if has_role(self, &role, &account) && get_role_expiry(self, &role, &account).is_none() {
+            return Err(AccessControlError::RoleRedundant)
+        }
+        self._add_member(role, account);
+        self.get_mut().expirations.insert((&role, &account), &expiry);
+        self._emit_role_granted(role, account, Some(Self::env().caller()));
+        Ok(())
+    }
+
+    default fn get_role_expiry(&self, role: RoleType, account: AccountId) -> Option<Timestamp> {
+        get_role_expiry(self, &role, &account)
+    }
+
     #[modifiers(only_role(get_role_admin(self, &role)))]
     default fn revoke_role(&mut self, role: RoleType, account: AccountId) -> Result<(), AccessControlError> {
-        check_role(self, &role, &account)?;
+        check_member(self, &role, &account)?;
         self._do_revoke_role(role, account);
         Ok(())
     }
@@ -87,7 +119,7 @@ impl<T: AccessControlStorage> AccessControl for T {
         if Self::env().caller() != account {
             return Err(AccessControlError::InvalidCaller)
         }
-        check_role(self, &role, &account)?;
+        check_member(self, &role, &account)?;
         self._do_revoke_role(role, account);
         Ok(())
     }
@@ -143,15 +175,17 @@ impl<T: AccessControlStorage> AccessControlInternal for T {
     }
 
     default fn _setup_role(&mut self, role: RoleType, member: AccountId) {
-        if !has_role(self, &role, &member) {
-            self.get_mut().members.insert((&role, &member), &());
+        if !has_role(self, &role, &member) || get_role_expiry(self, &role, &member).is_some() {
+            self._add_member(role, member);
+            self.get_mut().expirations.remove((&role, &member));
 
             self._emit_role_granted(role, member, None);
         }
     }
 
     default fn _do_revoke_role(&mut self, role: RoleType, account: AccountId) {
-        self.get_mut().members.remove((&role, &account));
+        self._remove_member(role, account);
+        self.get_mut().expirations.remove((&role, &account));
         self._emit_role_revoked(role, account, Self::env().caller());
     }
 
@@ -166,6 +200,26 @@ impl<T: AccessControlStorage> AccessControlInternal for T {
     }
 }
 
+pub trait AccessControlMembers {
+    /// Stores `account` as a member of `role`.
+    /// Extensions can override this function to track additional data about members.
+    fn _add_member(&mut self, role: RoleType, account: AccountId);
+
+    /// Removes `account` from members of `role`.
+    /// Extensions can override this function to track additional data about members.
+    fn _remove_member(&mut self, role: RoleType, account: AccountId);
+}
+
+impl<T: AccessControlStorage> AccessControlMembers for T {
+    default fn _add_member(&mut self, role: RoleType, account: AccountId) {
+        self.get_mut().members.insert((&role, &account), &());
+    }
+
+    default fn _remove_member(&mut self, role: RoleType, account: AccountId) {
+        self.get_mut().members.remove((&role, &account));
+    }
+}
+
 pub fn check_role<T: AccessControlStorage>(
     instance: &T,
     role: &RoleType,
@@ -177,8 +231,34 @@ pub fn check_role<T: AccessControlStorage>(
     Ok(())
 }
 
+/// Same as `check_role`, but ignores the expiry of time-bound grants, so expired members can still be revoked.
+pub fn check_member<T: AccessControlStorage>(
+    instance: &T,
+    role: &RoleType,
+    account: &AccountId,
+) -> Result<(), AccessControlError> {
+    if instance.get().members.get((role, account)).is_none() {
+        return Err(AccessControlError::MissingRole)
+    }
+    Ok(())
+}
+
 pub fn has_role<T: AccessControlStorage>(instance: &T, role: &RoleType, account: &AccountId) -> bool {
-    instance.get().members.get((role, account)).is_some()
+    if instance.get().members.get((role, account)).is_none() {
+        return false
+    }
+    match get_role_expiry(instance, role, account) {
+        Some(expiry) => T::env().block_timestamp() < expiry,
+        None => true,
+    }
+}
+
+pub fn get_role_expiry<T: AccessControlStorage>(
+    instance: &T,
+    role: &RoleType,
+    account: &AccountId,
+) -> Option<Timestamp> {
+    instance.get().expirations.get((role, account))
 }
 
 pub fn get_role_admin<T: AccessControlStorage>(instance: &T, role: &RoleType) -> RoleType {
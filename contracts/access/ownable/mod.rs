@@ -38,6 +38,7 @@ pub const STORAGE_KEY: [u8; 32] = ink_lang::blake2x256!("brush::OwnableData");
 #[brush::storage(STORAGE_KEY)]
 pub struct OwnableData {
     pub owner: AccountId,
+    pub pending_owner: Option<AccountId>,
     pub _reserved: Option<()>,
 }
 
@@ -66,6 +67,7 @@ impl<T: OwnableStorage> Ownable for T {
     default fn renounce_ownership(&mut self) -> Result<(), OwnableError> {
         let old_owner = self.get().owner.clone();
         self.get_mut().owner = ZERO_ADDRESS.into();
+        self.get_mut().pending_owner = None;
         self._emit_ownership_transferred_event(Some(old_owner), None);
         Ok(())
     }
@@ -76,16 +78,44 @@ impl<T: OwnableStorage> Ownable for T {
             return Err(OwnableError::NewOwnerIsZero)
         }
         let old_owner = self.get().owner.clone();
-        self.get_mut().owner = new_owner.clone();
-        self._emit_ownership_transferred_event(Some(old_owner), Some(new_owner));
+        self.get_mut().pending_owner = Some(new_owner.clone());
+        self._emit_ownership_transfer_started_event(old_owner, new_owner);
         Ok(())
     }
+
+    default fn accept_ownership(&mut self) -> Result<(), OwnableError> {
+        let caller = Self::env().caller();
+        if self.get().pending_owner != Some(caller) {
+            return Err(OwnableError::CallerIsNotPendingOwner)
+        }
+        let old_owner = self.get().owner.clone();
+        self.get_mut().owner = caller.clone();
+        self.get_mut().pending_owner = None;
+        self._emit_ownership_transferred_event(Some(old_owner), Some(caller));
+        Ok(())
+    }
+
+    #[modifiers(only_owner)]
+    default fn cancel_ownership_transfer(&mut self) -> Result<(), OwnableError> {
+        if self.get().pending_owner.is_none() {
+            return Err(OwnableError::NoPendingOwner)
+        }
+        self.get_mut().pending_owner = None;
+        Ok(())
+    }
+
+    default fn pending_owner(&self) -> Option<AccountId> {
+        self.get().pending_owner.clone()
+    }
 }
 
 pub trait OwnableInternal {
     /// User must override this method in their contract.
     fn _emit_ownership_transferred_event(&self, _previous_owner: Option<AccountId>, _new_owner: Option<AccountId>);
 
+    /// User must override this method in their contract.
+    fn _emit_ownership_transfer_started_event(&self, _previous_owner: AccountId, _new_owner: AccountId);
+
     fn _init_with_owner(&mut self, owner: AccountId);
 }
 
@@ -98,6 +128,9 @@ impl<T: OwnableStorage> OwnableInternal for T {
     ) {
     }
 
+    /// User must override this method in their contract.
+    default fn _emit_ownership_transfer_started_event(&self, _previous_owner: AccountId, _new_owner: AccountId) {}
+
     default fn _init_with_owner(&mut self, owner: AccountId) {
         self.get_mut().owner = owner;
         self._emit_ownership_transferred_event(None, Some(owner));
@@ -27,6 +27,7 @@ use brush::{
     traits::AccountId,
 };
 pub use derive::PausableStorage;
+use ink_storage::Mapping;
 
 pub const STORAGE_KEY: [u8; 32] = ink_lang::blake2x256!("brush::PausableData");
 
@@ -34,6 +35,8 @@ pub const STORAGE_KEY: [u8; 32] = ink_lang::blake2x256!("brush::PausableData");
 #[brush::storage(STORAGE_KEY)]
 pub struct PausableData {
     pub paused: bool,
+    pub paused_operations: OperationMask,
+    pub paused_asset_operations: Mapping<AccountId, OperationMask>,
     pub _reserved: Option<()>,
 }
 
@@ -67,10 +70,34 @@ where
     body(instance)
 }
 
+/// Modifier to make a function callable only when `operations` are not paused
+/// globally or for `asset`, and the contract is not paused.
+#[modifier_definition]
+pub fn when_not_paused_op<T, F, R, E>(
+    instance: &mut T,
+    body: F,
+    operations: OperationMask,
+    asset: Option<AccountId>,
+) -> Result<R, E>
+where
+    T: PausableStorage,
+    F: FnOnce(&mut T) -> Result<R, E>,
+    E: From<PausableError>,
+{
+    if let Err(err) = check_operation_not_paused(instance, operations, &asset) {
+        return Err(From::from(err))
+    }
+    body(instance)
+}
+
 impl<T: PausableStorage> Pausable for T {
     default fn paused(&self) -> bool {
         self.get().paused
     }
+
+    default fn operation_paused(&self, operations: OperationMask, asset: Option<AccountId>) -> bool {
+        operation_paused(self, operations, &asset)
+    }
 }
 
 pub trait PausableInternal {
@@ -91,6 +118,30 @@ pub trait PausableInternal {
     /// On success a `Unpaused` event is emitted.
     #[modifiers(when_paused)]
     fn _unpause<E: From<PausableError>>(&mut self) -> Result<(), E>;
+
+    /// User must override this method in their contract.
+    fn _emit_operations_paused_event(&self, _account: AccountId, _operations: OperationMask, _asset: Option<AccountId>);
+
+    /// User must override this method in their contract.
+    fn _emit_operations_unpaused_event(&self, _account: AccountId, _operations: OperationMask, _asset: Option<AccountId>);
+
+    /// Pauses `operations` globally if `asset` is `None`, otherwise only for `asset`.
+    ///
+    /// On success a `OperationsPaused` event is emitted.
+    fn _pause_operations<E: From<PausableError>>(
+        &mut self,
+        operations: OperationMask,
+        asset: Option<AccountId>,
+    ) -> Result<(), E>;
+
+    /// Unpauses `operations` globally if `asset` is `None`, otherwise only for `asset`.
+    ///
+    /// On success a `OperationsUnpaused` event is emitted.
+    fn _unpause_operations<E: From<PausableError>>(
+        &mut self,
+        operations: OperationMask,
+        asset: Option<AccountId>,
+    ) -> Result<(), E>;
 }
 
 impl<T: PausableStorage> PausableInternal for T {
@@ -111,4 +162,78 @@ impl<T: PausableStorage> PausableInternal for T {
         self._emit_unpaused_event(Self::env().caller());
         Ok(())
     }
+
+    default fn _emit_operations_paused_event(
+        &self,
+        _account: AccountId,
+        _operations: OperationMask,
+        _asset: Option<AccountId>,
+    ) {
+    }
+
+    default fn _emit_operations_unpaused_event(
+        &self,
+        _account: AccountId,
+        _operations: OperationMask,
+        _asset: Option<AccountId>,
+    ) {
+    }
+
+    default fn _pause_operations<E: From<PausableError>>(
+        &mut self,
+        operations: OperationMask,
+        asset: Option<AccountId>,
+    ) -> Result<(), E> {
+        let paused_operations = paused_operations(self, &asset);
+        set_paused_operations(self, &asset, paused_operations | operations);
+        self._emit_operations_paused_event(Self::env().caller(), operations, asset);
+        Ok(())
+    }
+
+    default fn _unpause_operations<E: From<PausableError>>(
+        &mut self,
+        operations: OperationMask,
+        asset: Option<AccountId>,
+    ) -> Result<(), E> {
+        let paused_operations = paused_operations(self, &asset);
+        if paused_operations & operations == 0 {
+            return Err(From::from(PausableError::NotPaused))
+        }
+        set_paused_operations(self, &asset, paused_operations & !operations);
+        self._emit_operations_unpaused_event(Self::env().caller(), operations, asset);
+        Ok(())
+    }
+}
+
+pub fn check_operation_not_paused<T: PausableStorage>(
+    instance: &T,
+    operations: OperationMask,
+    asset: &Option<AccountId>,
+) -> Result<(), PausableError> {
+    if operation_paused(instance, operations, asset) {
+        return Err(PausableError::Paused)
+    }
+    Ok(())
+}
+
+pub fn operation_paused<T: PausableStorage>(instance: &T, operations: OperationMask, asset: &Option<AccountId>) -> bool {
+    if instance.get().paused || paused_operations(instance, &None) & operations != 0 {
+        return true
+    }
+    asset.is_some() && paused_operations(instance, asset) & operations != 0
+}
+
+/// Returns operations paused for `asset` only, or the global paused operations for `None`.
+pub fn paused_operations<T: PausableStorage>(instance: &T, asset: &Option<AccountId>) -> OperationMask {
+    match asset {
+        Some(asset) => instance.get().paused_asset_operations.get(asset).unwrap_or(0),
+        None => instance.get().paused_operations,
+    }
+}
+
+fn set_paused_operations<T: PausableStorage>(instance: &mut T, asset: &Option<AccountId>, operations: OperationMask) {
+    match asset {
+        Some(asset) => instance.get_mut().paused_asset_operations.insert(asset, &operations),
+        None => instance.get_mut().paused_operations = operations,
+    }
 }
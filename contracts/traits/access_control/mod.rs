@@ -20,23 +20,31 @@
 // WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
 pub use crate::traits::errors::AccessControlError;
-use brush::traits::AccountId;
+use brush::traits::{
+    AccountId,
+    Timestamp,
+};
 
 pub type RoleType = u32;
 
+pub mod extensions {
+    pub mod enumerable;
+}
+
 #[brush::wrapper]
 pub type AccessControlRef = dyn AccessControl;
 
 /// Contract module that allows children to implement role-based access
 /// control mechanisms. This is a lightweight version that doesn't allow enumerating role
 /// members except through off-chain means by accessing the contract event logs.
+/// Use the `AccessControlEnumerable` extension to enumerate role members on-chain.
 ///
 /// Roles can be granted and revoked dynamically via the `grant_role` and
 /// `revoke_role`. functions. Each role has an associated admin role, and only
 /// accounts that have a role's admin role can call `grant_role` and `revoke_role`.
 #[brush::trait_definition]
 pub trait AccessControl {
-    /// Returns `true` if `account` has been granted `role`.
+    /// Returns `true` if `account` has been granted `role` and the grant has not expired.
     #[ink(message)]
     fn has_role(&self, role: RoleType, address: AccountId) -> bool;
 
@@ -45,16 +53,41 @@ pub trait AccessControl {
     fn get_role_admin(&self, role: RoleType) -> RoleType;
 
     /// Grants `role` to `account`.
+    /// A time-bound grant of `account` is turned into a permanent one.
     ///
     /// On success a `RoleGranted` event is emitted.
     ///
     /// # Errors
     ///
     /// Returns with `MissingRole` error if caller can't grant the role.
-    /// Returns with `RoleRedundant` error `account` has `role`.
+    /// Returns with `RoleRedundant` error `account` has `role` permanently.
     #[ink(message)]
     fn grant_role(&mut self, role: RoleType, account: AccountId) -> Result<(), AccessControlError>;
 
+    /// Grants `role` to `account` until the `expiry` timestamp.
+    /// After `expiry` the account is treated as if it doesn't have `role`.
+    /// Calling it again for a time-bound member updates the expiry,
+    /// `grant_role` turns the grant into a permanent one.
+    ///
+    /// On success a `RoleGranted` event is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns with `MissingRole` error if caller can't grant the role.
+    /// Returns with `InvalidExpiry` error if `expiry` is not in the future.
+    /// Returns with `RoleRedundant` error if `account` has `role` permanently.
+    #[ink(message)]
+    fn grant_role_until(
+        &mut self,
+        role: RoleType,
+        account: AccountId,
+        expiry: Timestamp,
+    ) -> Result<(), AccessControlError>;
+
+    /// Returns the expiry of the `role` grant of `account`, `None` if the grant is permanent or doesn't exist.
+    #[ink(message)]
+    fn get_role_expiry(&self, role: RoleType, account: AccountId) -> Option<Timestamp>;
+
     /// Revokes `role` from `account`.
     ///
     /// On success a `RoleRevoked` event is emitted.
@@ -62,6 +95,7 @@ pub trait AccessControl {
     /// # Errors
     ///
     /// Returns with `MissingRole` error if caller can't grant the `role` or if `account` doesn't have `role`.
+    /// Expired grants can still be revoked.
     #[ink(message)]
     fn revoke_role(&mut self, role: RoleType, account: AccountId) -> Result<(), AccessControlError>;
 
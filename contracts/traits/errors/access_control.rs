@@ -27,4 +27,5 @@ pub enum AccessControlError {
     InvalidCaller,
     MissingRole,
     RoleRedundant,
+    InvalidExpiry,
 }
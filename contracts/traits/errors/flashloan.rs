@@ -42,6 +42,10 @@ impl From<OwnableError> for FlashBorrowerError {
                 FlashBorrowerError::FlashloanRejected(String::from("O::CallerIsNotOwner"))
             }
             OwnableError::NewOwnerIsZero => FlashBorrowerError::FlashloanRejected(String::from("O::NewOwnerIsZero")),
+            OwnableError::CallerIsNotPendingOwner => {
+                FlashBorrowerError::FlashloanRejected(String::from("O::CallerIsNotPendingOwner"))
+            }
+            OwnableError::NoPendingOwner => FlashBorrowerError::FlashloanRejected(String::from("O::NoPendingOwner")),
         }
     }
 }
@@ -56,6 +60,9 @@ impl From<AccessControlError> for FlashBorrowerError {
             AccessControlError::InvalidCaller => {
                 FlashBorrowerError::FlashloanRejected(String::from("AC::InvalidCaller"))
             }
+            AccessControlError::InvalidExpiry => {
+                FlashBorrowerError::FlashloanRejected(String::from("AC::InvalidExpiry"))
+            }
         }
     }
 }
@@ -127,6 +134,10 @@ impl From<OwnableError> for FlashLenderError {
         match ownable {
             OwnableError::CallerIsNotOwner => FlashLenderError::Custom(String::from("O::CallerIsNotOwner")),
             OwnableError::NewOwnerIsZero => FlashLenderError::Custom(String::from("O::NewOwnerIsZero")),
+            OwnableError::CallerIsNotPendingOwner => {
+                FlashLenderError::Custom(String::from("O::CallerIsNotPendingOwner"))
+            }
+            OwnableError::NoPendingOwner => FlashLenderError::Custom(String::from("O::NoPendingOwner")),
         }
     }
 }
@@ -137,6 +148,7 @@ impl From<AccessControlError> for FlashLenderError {
             AccessControlError::MissingRole => FlashLenderError::Custom(String::from("AC::MissingRole")),
             AccessControlError::RoleRedundant => FlashLenderError::Custom(String::from("AC::RoleRedundant")),
             AccessControlError::InvalidCaller => FlashLenderError::Custom(String::from("AC::InvalidCaller")),
+            AccessControlError::InvalidExpiry => FlashLenderError::Custom(String::from("AC::InvalidExpiry")),
         }
     }
 }
@@ -26,4 +26,6 @@
 pub enum OwnableError {
     CallerIsNotOwner,
     NewOwnerIsZero,
+    CallerIsNotPendingOwner,
+    NoPendingOwner,
 }
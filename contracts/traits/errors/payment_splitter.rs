@@ -47,6 +47,7 @@ impl From<AccessControlError> for PaymentSplitterError {
             AccessControlError::MissingRole => PaymentSplitterError::Custom(String::from("AC::MissingRole")),
             AccessControlError::RoleRedundant => PaymentSplitterError::Custom(String::from("AC::RoleRedundant")),
             AccessControlError::InvalidCaller => PaymentSplitterError::Custom(String::from("AC::InvalidCaller")),
+            AccessControlError::InvalidExpiry => PaymentSplitterError::Custom(String::from("AC::InvalidExpiry")),
         }
     }
 }
@@ -56,6 +57,10 @@ impl From<OwnableError> for PaymentSplitterError {
         match ownable {
             OwnableError::CallerIsNotOwner => PaymentSplitterError::Custom(String::from("O::CallerIsNotOwner")),
             OwnableError::NewOwnerIsZero => PaymentSplitterError::Custom(String::from("O::NewOwnerIsZero")),
+            OwnableError::CallerIsNotPendingOwner => {
+                PaymentSplitterError::Custom(String::from("O::CallerIsNotPendingOwner"))
+            }
+            OwnableError::NoPendingOwner => PaymentSplitterError::Custom(String::from("O::NoPendingOwner")),
         }
     }
 }
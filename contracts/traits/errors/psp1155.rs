@@ -48,6 +48,8 @@ impl From<OwnableError> for PSP1155Error {
         match ownable {
             OwnableError::CallerIsNotOwner => PSP1155Error::Custom(String::from("O::CallerIsNotOwner")),
             OwnableError::NewOwnerIsZero => PSP1155Error::Custom(String::from("O::NewOwnerIsZero")),
+            OwnableError::CallerIsNotPendingOwner => PSP1155Error::Custom(String::from("O::CallerIsNotPendingOwner")),
+            OwnableError::NoPendingOwner => PSP1155Error::Custom(String::from("O::NoPendingOwner")),
         }
     }
 }
@@ -58,6 +60,7 @@ impl From<AccessControlError> for PSP1155Error {
             AccessControlError::MissingRole => PSP1155Error::Custom(String::from("AC::MissingRole")),
             AccessControlError::RoleRedundant => PSP1155Error::Custom(String::from("AC::RoleRedundant")),
             AccessControlError::InvalidCaller => PSP1155Error::Custom(String::from("AC::InvalidCaller")),
+            AccessControlError::InvalidExpiry => PSP1155Error::Custom(String::from("AC::InvalidExpiry")),
         }
     }
 }
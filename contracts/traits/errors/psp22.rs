@@ -50,6 +50,8 @@ impl From<OwnableError> for PSP22Error {
         match ownable {
             OwnableError::CallerIsNotOwner => PSP22Error::Custom(String::from("O::CallerIsNotOwner")),
             OwnableError::NewOwnerIsZero => PSP22Error::Custom(String::from("O::NewOwnerIsZero")),
+            OwnableError::CallerIsNotPendingOwner => PSP22Error::Custom(String::from("O::CallerIsNotPendingOwner")),
+            OwnableError::NoPendingOwner => PSP22Error::Custom(String::from("O::NoPendingOwner")),
         }
     }
 }
@@ -60,6 +62,7 @@ impl From<AccessControlError> for PSP22Error {
             AccessControlError::MissingRole => PSP22Error::Custom(String::from("AC::MissingRole")),
             AccessControlError::RoleRedundant => PSP22Error::Custom(String::from("AC::RoleRedundant")),
             AccessControlError::InvalidCaller => PSP22Error::Custom(String::from("AC::InvalidCaller")),
+            AccessControlError::InvalidExpiry => PSP22Error::Custom(String::from("AC::InvalidExpiry")),
         }
     }
 }
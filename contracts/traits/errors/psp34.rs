@@ -50,6 +50,8 @@ impl From<OwnableError> for PSP34Error {
         match ownable {
             OwnableError::CallerIsNotOwner => PSP34Error::Custom(String::from("O::CallerIsNotOwner")),
             OwnableError::NewOwnerIsZero => PSP34Error::Custom(String::from("O::NewOwnerIsZero")),
+            OwnableError::CallerIsNotPendingOwner => PSP34Error::Custom(String::from("O::CallerIsNotPendingOwner")),
+            OwnableError::NoPendingOwner => PSP34Error::Custom(String::from("O::NoPendingOwner")),
         }
     }
 }
@@ -60,6 +62,7 @@ impl From<AccessControlError> for PSP34Error {
             AccessControlError::MissingRole => PSP34Error::Custom(String::from("AC::MissingRole")),
             AccessControlError::RoleRedundant => PSP34Error::Custom(String::from("AC::RoleRedundant")),
             AccessControlError::InvalidCaller => PSP34Error::Custom(String::from("AC::InvalidCaller")),
+            AccessControlError::InvalidExpiry => PSP34Error::Custom(String::from("AC::InvalidExpiry")),
         }
     }
 }
@@ -54,6 +54,9 @@ impl From<AccessControlError> for TimelockControllerError {
             AccessControlError::InvalidCaller => {
                 TimelockControllerError::AccessControlError(AccessControlError::InvalidCaller)
             }
+            AccessControlError::InvalidExpiry => {
+                TimelockControllerError::AccessControlError(AccessControlError::InvalidExpiry)
+            }
         }
     }
 }
@@ -63,6 +66,10 @@ impl From<OwnableError> for TimelockControllerError {
         match ownable {
             OwnableError::CallerIsNotOwner => TimelockControllerError::Custom(String::from("O::CallerIsNotOwner")),
             OwnableError::NewOwnerIsZero => TimelockControllerError::Custom(String::from("O::NewOwnerIsZero")),
+            OwnableError::CallerIsNotPendingOwner => {
+                TimelockControllerError::Custom(String::from("O::CallerIsNotPendingOwner"))
+            }
+            OwnableError::NoPendingOwner => TimelockControllerError::Custom(String::from("O::NoPendingOwner")),
         }
     }
 }
@@ -48,10 +48,12 @@ pub trait Ownable {
     #[ink(message)]
     fn renounce_ownership(&mut self) -> Result<(), OwnableError>;
 
-    /// Transfers ownership of the contract to a `new_owner`.
+    /// Starts the transfer of ownership of the contract to a `new_owner`.
+    /// The ownership is transferred only after `new_owner` calls `accept_ownership`.
+    /// Replaces the previous pending owner if there is one.
     /// Can only be called by the current owner.
     ///
-    /// On success a `OwnershipTransferred` event is emitted.
+    /// On success a `OwnershipTransferStarted` event is emitted.
     ///
     /// # Errors
     ///
@@ -60,4 +62,30 @@ pub trait Ownable {
     /// Panics with `NewOwnerIsZero` error if new owner's address is zero.
     #[ink(message)]
     fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), OwnableError>;
+
+    /// Finishes the transfer of ownership started by `transfer_ownership`.
+    /// Can only be called by the pending owner.
+    ///
+    /// On success a `OwnershipTransferred` event is emitted.
+    ///
+    /// # Errors
+    ///
+    /// Panics with `CallerIsNotPendingOwner` error if caller is not the pending owner.
+    #[ink(message)]
+    fn accept_ownership(&mut self) -> Result<(), OwnableError>;
+
+    /// Cancels the pending transfer of ownership.
+    /// Can only be called by the current owner.
+    ///
+    /// # Errors
+    ///
+    /// Panics with `CallerIsNotOwner` error if caller is not owner.
+    ///
+    /// Panics with `NoPendingOwner` error if there is no pending transfer.
+    #[ink(message)]
+    fn cancel_ownership_transfer(&mut self) -> Result<(), OwnableError>;
+
+    /// Returns the address of the pending owner, if there is a pending transfer.
+    #[ink(message)]
+    fn pending_owner(&self) -> Option<AccountId>;
 }
@@ -20,6 +20,10 @@
 // WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
 pub use crate::traits::errors::PausableError;
+use brush::traits::AccountId;
+
+/// Bitmask of operations, each bit represents one operation defined by the contract.
+pub type OperationMask = u32;
 
 #[brush::wrapper]
 pub type PausableRef = dyn Pausable;
@@ -31,4 +35,9 @@ pub trait Pausable {
     /// Returns true if the contract is paused, and false otherwise.
     #[ink(message)]
     fn paused(&self) -> bool;
+
+    /// Returns true if any of `operations` is paused globally or for `asset`, and false otherwise.
+    /// The operation is also considered paused while the whole contract is paused.
+    #[ink(message)]
+    fn operation_paused(&self, operations: OperationMask, asset: Option<AccountId>) -> bool;
 }
@@ -0,0 +1,221 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+/// This contract represents the governance token of the protocol.
+/// It keeps a history of balances, so the governor can count votes at the block when the voting started.
+#[brush::contract]
+pub mod governance_token {
+    /// imports of libraries and traits needed
+    use ink_prelude::string::String;
+    use ink_storage::Mapping;
+    use ink_storage::traits::SpreadAllocate;
+    use brush::contracts::psp22::*;
+    use brush::contracts::psp22::extensions::metadata::*;
+    use liquidity_pool_protocol::traits::governance_token::*;
+
+    /// Defining storage and its components and also deriving needed imports to our storage.
+    #[ink(storage)]
+    #[derive(Default, SpreadAllocate, PSP22Storage, PSP22MetadataStorage)]
+    /// Our contracts variables are stored in this struct.
+    pub struct GovernanceTokenContract {
+        #[PSP22StorageField]
+        psp22: PSP22Data,
+        #[PSP22MetadataStorageField]
+        metadata: PSP22MetadataData,
+        /// We will store pair (account, index) <--> (block number, balance) checkpoints here.
+        checkpoints: Mapping<(AccountId, u32), (BlockNumber, Balance)>,
+        /// We will store number of checkpoints of each account here.
+        checkpoint_counts: Mapping<AccountId, u32>,
+        /// We will store pair index <--> (block number, total supply) checkpoints here.
+        supply_checkpoints: Mapping<u32, (BlockNumber, Balance)>,
+        /// We will store number of total supply checkpoints here.
+        supply_checkpoint_count: u32,
+    }
+
+    /// We inherit the implementation of the PSP22 trait.
+    impl PSP22 for GovernanceTokenContract {}
+    /// We inherit the implementation of the PSP22Metadata trait.
+    impl PSP22Metadata for GovernanceTokenContract {}
+    /// We write checkpoints after every change of balances.
+    impl PSP22Transfer for GovernanceTokenContract {
+        /// # Returns
+        /// Returns a Ok(()) with success and PSP22Error otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `from` AccountId tokens are transferred from, None when minting
+        /// * `to` AccountId tokens are transferred to, None when burning
+        /// * `_amount` how many tokens were transferred
+        ///
+        /// # Description
+        /// Hook called by PSP22 after every transfer, mint and burn, it stores new balances of both sides.
+        fn _after_token_transfer(&mut self, from: Option<&AccountId>, to: Option<&AccountId>, _amount: &Balance) -> Result<(), PSP22Error> {
+            // store new balance of the sender
+            if let Some(from) = from {
+                let balance = self.balance_of(*from);
+                self.write_checkpoint(*from, balance);
+            }
+            // store new balance of the receiver
+            if let Some(to) = to {
+                let balance = self.balance_of(*to);
+                self.write_checkpoint(*to, balance);
+            }
+            // total supply changes only with mint and burn
+            if from.is_none() || to.is_none() {
+                let total_supply = self.total_supply();
+                self.write_supply_checkpoint(total_supply);
+            }
+            Ok(())
+        }
+    }
+    /// We implement functions declared in GovernanceToken trait.
+    impl GovernanceToken for GovernanceTokenContract {
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `account` - AccountId of voter
+        ///
+        /// # Description
+        /// get_votes returns current voting power of account, which is equal to its balance.
+        #[ink(message)]
+        fn get_votes(&self, account: AccountId) -> Balance {
+            self.balance_of(account)
+        }
+
+        /// # Returns
+        /// Returns a Balance with success and PSP22Error otherwise
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `account` - AccountId of voter
+        /// * `block_number` - BlockNumber of already mined block
+        ///
+        /// # Description
+        /// get_past_votes returns voting power of account at the end of given block.
+        #[ink(message)]
+        fn get_past_votes(&self, account: AccountId, block_number: BlockNumber) -> Result<Balance, PSP22Error> {
+            // the balance of current block can still change
+            if block_number >= self.env().block_number() {
+                return Err(PSP22Error::Custom(String::from("Block not yet mined")))
+            }
+            let count = self.checkpoint_counts.get(&account).unwrap_or(0);
+            Ok(Self::lookup(count, block_number, |index| self.checkpoints.get((&account, &index)).unwrap_or_default()))
+        }
+
+        /// # Returns
+        /// Returns a Balance with success and PSP22Error otherwise
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `block_number` - BlockNumber of already mined block
+        ///
+        /// # Description
+        /// get_past_total_supply returns total supply at the end of given block.
+        #[ink(message)]
+        fn get_past_total_supply(&self, block_number: BlockNumber) -> Result<Balance, PSP22Error> {
+            // the total supply of current block can still change
+            if block_number >= self.env().block_number() {
+                return Err(PSP22Error::Custom(String::from("Block not yet mined")))
+            }
+            Ok(Self::lookup(self.supply_checkpoint_count, block_number, |index| self.supply_checkpoints.get(&index).unwrap_or_default()))
+        }
+    }
+    /// Implementation of GovernanceToken smart contract.
+    impl GovernanceTokenContract {
+        /// # Returns
+        /// Returns Self
+        ///
+        /// # Arguments
+        /// * `name` optional String for specifying our token name in Metadata
+        /// * `symbol` optional String for specifying our token symbol in Metadata
+        /// * `total_supply` Balance of tokens minted to the caller
+        ///
+        /// # Description
+        /// Constructor for initializing our contract.
+        #[ink(constructor)]
+        pub fn new(name: Option<String>, symbol: Option<String>, total_supply: Balance) -> Self {
+            ink_lang::codegen::initialize_contract(|instance: &mut GovernanceTokenContract| {
+                // Set metadata variables.
+                instance.metadata.name = name;
+                instance.metadata.symbol = symbol;
+                instance.metadata.decimals = 18;
+                // Mint initial supply to the caller.
+                assert!(instance._mint(instance.env().caller(), total_supply).is_ok());
+            })
+        }
+
+        /// # Returns
+        /// Without a return value
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `account` - AccountId which balance changed
+        /// * `balance` - new Balance of account
+        ///
+        /// # Description
+        /// Helper function to store balance of account in current block.
+        fn write_checkpoint(&mut self, account: AccountId, balance: Balance) {
+            let block_number = self.env().block_number();
+            let count = self.checkpoint_counts.get(&account).unwrap_or(0);
+            // overwrite the last checkpoint if it is from the current block
+            if count > 0 && self.checkpoints.get((&account, &(count - 1))).unwrap_or_default().0 == block_number {
+                self.checkpoints.insert((&account, &(count - 1)), &(block_number, balance));
+                return
+            }
+            self.checkpoints.insert((&account, &count), &(block_number, balance));
+            self.checkpoint_counts.insert(&account, &(count + 1));
+        }
+
+        /// # Returns
+        /// Without a return value
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `total_supply` - new total supply
+        ///
+        /// # Description
+        /// Helper function to store total supply in current block.
+        fn write_supply_checkpoint(&mut self, total_supply: Balance) {
+            let block_number = self.env().block_number();
+            let count = self.supply_checkpoint_count;
+            // overwrite the last checkpoint if it is from the current block
+            if count > 0 && self.supply_checkpoints.get(&(count - 1)).unwrap_or_default().0 == block_number {
+                self.supply_checkpoints.insert(&(count - 1), &(block_number, total_supply));
+                return
+            }
+            self.supply_checkpoints.insert(&count, &(block_number, total_supply));
+            self.supply_checkpoint_count = count + 1;
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `count` - number of checkpoints
+        /// * `block_number` - BlockNumber we look for
+        /// * `checkpoint` - function returning checkpoint by its index
+        ///
+        /// # Description
+        /// Helper function to find value of the last checkpoint made at or before block_number using binary search.
+        fn lookup<F: Fn(u32) -> (BlockNumber, Balance)>(count: u32, block_number: BlockNumber, checkpoint: F) -> Balance {
+            // find the first checkpoint made after block_number
+            let mut low = 0;
+            let mut high = count;
+            while low < high {
+                let middle = low + (high - low) / 2;
+                if checkpoint(middle).0 > block_number {
+                    high = middle;
+                } else {
+                    low = middle + 1;
+                }
+            }
+            // the previous one holds the value, there is none when the account had no balance yet
+            if high == 0 {
+                return 0
+            }
+            checkpoint(high - 1).1
+        }
+    }
+}
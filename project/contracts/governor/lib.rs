@@ -0,0 +1,506 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+/// This contract is responsible for on-chain governance of the protocol.
+/// Holders of the governance token vote on proposals made of calls to other contracts, e.g. PoolManagerRef,
+/// successful proposals are executed after the timelock delay.
+/// The governor is supposed to be granted the ADMIN role of the liquidity pool manager.
+#[brush::contract]
+pub mod governor {
+    /// imports of libraries and traits needed
+    use brush::contracts::access_control::*;
+    use ink_storage::traits::SpreadAllocate;
+    use ink_storage::Mapping;
+    use ink_lang::codegen::Env;
+    use ink_prelude::vec::Vec;
+    use ink_prelude::string::String;
+    use brush::modifiers;
+    use liquidity_pool_protocol::traits::governance_token::GovernanceTokenRef;
+    use liquidity_pool_protocol::traits::governor::*;
+    use liquidity_pool_protocol::traits::transaction::*;
+
+    /// Constants trait_definition
+    /// Admin role, it can change governance settings and it is held by the governor itself,
+    /// so settings are changed only by proposals
+    pub const ADMIN: RoleType = 0;
+    /// Canceller role, it can cancel any proposal which was not executed yet
+    pub const CANCELLER: RoleType = 1;
+    /// denominator of the quorum numerator, quorum is in percent of total supply
+    pub const QUORUM_DENOMINATOR: Balance = 100;
+
+    /// Defining storage and its components and also deriving needed imports to our storage.
+    #[ink(storage)]
+    #[derive(Default, SpreadAllocate, AccessControlStorage)]
+    /// Our contracts variables are stored in this struct.
+    pub struct GovernorContract {
+        #[AccessControlStorageField]
+        access: AccessControlData,
+        /// AccountId of token which balances are used as votes
+        governance_token: AccountId,
+        /// number of blocks between proposal creation and the start of voting
+        voting_delay: BlockNumber,
+        /// number of blocks the voting lasts
+        voting_period: BlockNumber,
+        /// percent of total supply which has to vote for or abstain for the proposal to be valid
+        quorum_numerator: Balance,
+        /// number of votes needed to create a proposal
+        proposal_threshold: Balance,
+        /// time between queuing and execution of successful proposal
+        timelock_delay: Timestamp,
+        /// Id of last created proposal
+        last_proposal_id: ProposalId,
+        /// mapping from proposal Id to proposal
+        proposals: Mapping<ProposalId, Proposal>,
+        /// mapping of accounts which already voted on proposal
+        votes: Mapping<(ProposalId, AccountId), VoteType>,
+    }
+
+    /// We inherit the implementation of the AccessControl trait.
+    impl AccessControl for GovernorContract {}
+    /// We implement functions declared in Governor trait.
+    impl Governor for GovernorContract {
+        /// # Returns
+        /// Returns a ProposalId with success and GovernorError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `transactions` - Vec of encoded calls executed when the proposal passes
+        /// * `description` - String describing the proposal
+        ///
+        /// # Description
+        /// propose function creates a new proposal, voting starts after voting_delay blocks.
+        /// Proposer needs at least proposal_threshold votes in the previous block.
+        #[ink(message)]
+        fn propose(&mut self, transactions: Vec<Transaction>, description: String) -> Result<ProposalId, GovernorError> {
+            // proposal without calls has nothing to execute
+            if transactions.is_empty() {
+                return Err(GovernorError::EmptyProposal)
+            }
+            let proposer = self.env().caller();
+            let block_number = self.env().block_number();
+            // check the voting power of the proposer
+            if self.proposal_threshold > 0 {
+                let votes = GovernanceTokenRef::get_past_votes(&self.governance_token, proposer, block_number.saturating_sub(1))?;
+                if votes < self.proposal_threshold {
+                    return Err(GovernorError::BelowProposalThreshold)
+                }
+            }
+            // votes are counted at vote_start block
+            let vote_start = block_number + self.voting_delay;
+            let proposal = Proposal {
+                proposer,
+                transactions,
+                description,
+                vote_start,
+                vote_end: vote_start + self.voting_period,
+                for_votes: 0,
+                against_votes: 0,
+                abstain_votes: 0,
+                eta: None,
+                executed: false,
+                canceled: false,
+            };
+            self.last_proposal_id += 1;
+            let proposal_id = self.last_proposal_id;
+            self.proposals.insert(&proposal_id, &proposal);
+            Ok(proposal_id)
+        }
+
+        /// # Returns
+        /// Returns a Balance of votes with success and GovernorError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `proposal_id` - Id of proposal
+        /// * `vote` - VoteType of the vote (Against, For, Abstain)
+        ///
+        /// # Description
+        /// cast_vote function votes on an active proposal with the balance of the caller at the vote_start block.
+        #[ink(message)]
+        fn cast_vote(&mut self, proposal_id: ProposalId, vote: VoteType) -> Result<Balance, GovernorError> {
+            let voter = self.env().caller();
+            // voting is possible only during the voting period
+            if self.get_proposal_state(proposal_id)? != ProposalState::Active {
+                return Err(GovernorError::ProposalNotActive)
+            }
+            // each account votes only once
+            if self.has_voted(proposal_id, voter) {
+                return Err(GovernorError::AlreadyVoted)
+            }
+            let mut proposal = self.proposals.get(&proposal_id).unwrap();
+            // get voting power from the snapshot
+            let weight = GovernanceTokenRef::get_past_votes(&self.governance_token, voter, proposal.vote_start)?;
+            match vote {
+                VoteType::Against => proposal.against_votes += weight,
+                VoteType::For => proposal.for_votes += weight,
+                VoteType::Abstain => proposal.abstain_votes += weight,
+            }
+            self.proposals.insert(&proposal_id, &proposal);
+            self.votes.insert((&proposal_id, &voter), &vote);
+            Ok(weight)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and GovernorError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `proposal_id` - Id of proposal
+        ///
+        /// # Description
+        /// queue function starts the timelock of succeeded proposal, it can be executed after timelock_delay.
+        #[ink(message)]
+        fn queue(&mut self, proposal_id: ProposalId) -> Result<(), GovernorError> {
+            if self.get_proposal_state(proposal_id)? != ProposalState::Succeeded {
+                return Err(GovernorError::ProposalNotSucceeded)
+            }
+            let mut proposal = self.proposals.get(&proposal_id).unwrap();
+            proposal.eta = Some(self.env().block_timestamp() + self.timelock_delay);
+            self.proposals.insert(&proposal_id, &proposal);
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and GovernorError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `proposal_id` - Id of proposal
+        ///
+        /// # Description
+        /// execute function calls all transactions of queued proposal after its timelock passed.
+        /// Results of the calls are not decoded, only failed calls are reported.
+        #[ink(message, payable)]
+        fn execute(&mut self, proposal_id: ProposalId) -> Result<(), GovernorError> {
+            if self.get_proposal_state(proposal_id)? != ProposalState::Queued {
+                return Err(GovernorError::ProposalNotQueued)
+            }
+            let mut proposal = self.proposals.get(&proposal_id).unwrap();
+            if self.env().block_timestamp() < proposal.eta.unwrap() {
+                return Err(GovernorError::TimelockNotReady)
+            }
+            // mark proposal executed before the calls, so it can't be executed again by reentrancy
+            proposal.executed = true;
+            self.proposals.insert(&proposal_id, &proposal);
+            // call every transaction of the proposal
+            for transaction in proposal.transactions.iter() {
+                call_transaction(transaction).map_err(|_| GovernorError::TransactionFailed)?;
+            }
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and GovernorError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `proposal_id` - Id of proposal
+        ///
+        /// # Description
+        /// cancel function cancels proposal which was not executed yet, only proposer or CANCELLER can call it.
+        #[ink(message)]
+        fn cancel(&mut self, proposal_id: ProposalId) -> Result<(), GovernorError> {
+            let caller = self.env().caller();
+            let state = self.get_proposal_state(proposal_id)?;
+            if state == ProposalState::Executed || state == ProposalState::Canceled {
+                return Err(GovernorError::ProposalAlreadyFinished)
+            }
+            let mut proposal = self.proposals.get(&proposal_id).unwrap();
+            if proposal.proposer != caller && !self.has_role(CANCELLER, caller) {
+                return Err(GovernorError::NotTheProposer)
+            }
+            proposal.canceled = true;
+            self.proposals.insert(&proposal_id, &proposal);
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a ProposalState with success and GovernorError otherwise
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `proposal_id` - Id of proposal
+        ///
+        /// # Description
+        /// get_proposal_state function returns current state of proposal.
+        /// Proposal succeeds when for and abstain votes reach the quorum and there are more for than against votes.
+        #[ink(message)]
+        fn get_proposal_state(&self, proposal_id: ProposalId) -> Result<ProposalState, GovernorError> {
+            let proposal = self.proposals.get(&proposal_id).ok_or(GovernorError::ProposalNotFound)?;
+            if proposal.executed {
+                return Ok(ProposalState::Executed)
+            }
+            if proposal.canceled {
+                return Ok(ProposalState::Canceled)
+            }
+            let block_number = self.env().block_number();
+            // snapshot block has to be mined before voting
+            if block_number <= proposal.vote_start {
+                return Ok(ProposalState::Pending)
+            }
+            if block_number <= proposal.vote_end {
+                return Ok(ProposalState::Active)
+            }
+            // check quorum and majority
+            let quorum = self.get_quorum(proposal.vote_start)?;
+            if proposal.for_votes + proposal.abstain_votes < quorum || proposal.for_votes <= proposal.against_votes {
+                return Ok(ProposalState::Defeated)
+            }
+            if proposal.eta.is_none() {
+                return Ok(ProposalState::Succeeded)
+            }
+            Ok(ProposalState::Queued)
+        }
+
+        /// # Returns
+        /// Returns an Option of Proposal
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `proposal_id` - Id of proposal
+        ///
+        /// # Description
+        /// get_proposal function returns proposal by its Id.
+        #[ink(message)]
+        fn get_proposal(&self, proposal_id: ProposalId) -> Option<Proposal> {
+            self.proposals.get(&proposal_id)
+        }
+
+        /// # Returns
+        /// Returns a bool
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `proposal_id` - Id of proposal
+        /// * `account` - AccountId of voter
+        ///
+        /// # Description
+        /// has_voted function returns whether account already voted on proposal.
+        #[ink(message)]
+        fn has_voted(&self, proposal_id: ProposalId, account: AccountId) -> bool {
+            self.votes.get((&proposal_id, &account)).is_some()
+        }
+
+        /// # Returns
+        /// Returns a Balance with success and GovernorError otherwise
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `block_number` - BlockNumber of already mined block
+        ///
+        /// # Description
+        /// get_quorum function returns number of votes needed at block_number, computed from past total supply.
+        #[ink(message)]
+        fn get_quorum(&self, block_number: BlockNumber) -> Result<Balance, GovernorError> {
+            let total_supply = GovernanceTokenRef::get_past_total_supply(&self.governance_token, block_number)?;
+            Ok(total_supply * self.quorum_numerator / QUORUM_DENOMINATOR)
+        }
+
+        /// # Returns
+        /// Returns an AccountId
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        ///
+        /// # Description
+        /// get_governance_token function returns AccountId of the token used for voting.
+        #[ink(message)]
+        fn get_governance_token(&self) -> AccountId {
+            self.governance_token
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and GovernorError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `voting_delay` - new number of blocks between proposal creation and the start of voting
+        ///
+        /// # Description
+        /// set_voting_delay is ADMIN function, so it is called by governor itself through a proposal.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        fn set_voting_delay(&mut self, voting_delay: BlockNumber) -> Result<(), GovernorError> {
+            self.voting_delay = voting_delay;
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and GovernorError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `voting_period` - new number of blocks the voting lasts, has to be greater than 0
+        ///
+        /// # Description
+        /// set_voting_period is ADMIN function, so it is called by governor itself through a proposal.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        fn set_voting_period(&mut self, voting_period: BlockNumber) -> Result<(), GovernorError> {
+            if voting_period == 0 {
+                return Err(GovernorError::InvalidParameter)
+            }
+            self.voting_period = voting_period;
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and GovernorError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `quorum_numerator` - new quorum in percent of total supply
+        ///
+        /// # Description
+        /// set_quorum_numerator is ADMIN function, so it is called by governor itself through a proposal.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        fn set_quorum_numerator(&mut self, quorum_numerator: Balance) -> Result<(), GovernorError> {
+            if quorum_numerator > QUORUM_DENOMINATOR {
+                return Err(GovernorError::InvalidParameter)
+            }
+            self.quorum_numerator = quorum_numerator;
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and GovernorError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `proposal_threshold` - new number of votes needed to create a proposal
+        ///
+        /// # Description
+        /// set_proposal_threshold is ADMIN function, so it is called by governor itself through a proposal.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        fn set_proposal_threshold(&mut self, proposal_threshold: Balance) -> Result<(), GovernorError> {
+            self.proposal_threshold = proposal_threshold;
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and GovernorError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `timelock_delay` - new time between queuing and execution of proposal
+        ///
+        /// # Description
+        /// set_timelock_delay is ADMIN function, so it is called by governor itself through a proposal.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        fn set_timelock_delay(&mut self, timelock_delay: Timestamp) -> Result<(), GovernorError> {
+            self.timelock_delay = timelock_delay;
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a BlockNumber
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        ///
+        /// # Description
+        /// get_voting_delay function returns number of blocks between proposal creation and the start of voting.
+        #[ink(message)]
+        fn get_voting_delay(&self) -> BlockNumber {
+            self.voting_delay
+        }
+
+        /// # Returns
+        /// Returns a BlockNumber
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        ///
+        /// # Description
+        /// get_voting_period function returns number of blocks the voting lasts.
+        #[ink(message)]
+        fn get_voting_period(&self) -> BlockNumber {
+            self.voting_period
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        ///
+        /// # Description
+        /// get_quorum_numerator function returns quorum in percent of total supply.
+        #[ink(message)]
+        fn get_quorum_numerator(&self) -> Balance {
+            self.quorum_numerator
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        ///
+        /// # Description
+        /// get_proposal_threshold function returns number of votes needed to create a proposal.
+        #[ink(message)]
+        fn get_proposal_threshold(&self) -> Balance {
+            self.proposal_threshold
+        }
+
+        /// # Returns
+        /// Returns a Timestamp
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        ///
+        /// # Description
+        /// get_timelock_delay function returns time between queuing and execution of proposal.
+        #[ink(message)]
+        fn get_timelock_delay(&self) -> Timestamp {
+            self.timelock_delay
+        }
+    }
+
+    /// This contract will contain constructor of the governor.
+    impl GovernorContract {
+        /// # Returns
+        /// Returns Self
+        ///
+        /// # Arguments
+        /// * `governance_token` - AccountId of token which balances are used as votes
+        /// * `voting_delay` - number of blocks between proposal creation and the start of voting
+        /// * `voting_period` - number of blocks the voting lasts
+        /// * `quorum_numerator` - percent of total supply needed for valid voting
+        /// * `proposal_threshold` - number of votes needed to create a proposal
+        /// * `timelock_delay` - time between queuing and execution of proposal
+        ///
+        /// # Description
+        /// Constructor for initializing our contract.
+        #[ink(constructor, payable)]
+        pub fn new(
+            governance_token: AccountId,
+            voting_delay: BlockNumber,
+            voting_period: BlockNumber,
+            quorum_numerator: Balance,
+            proposal_threshold: Balance,
+            timelock_delay: Timestamp,
+        ) -> Self {
+            // Use initialize_contract from ink_lang::codegen library.
+            ink_lang::codegen::initialize_contract(|instance: &mut GovernorContract| {
+                assert!(voting_period > 0 && quorum_numerator <= QUORUM_DENOMINATOR);
+                // governor is the admin of itself, so settings are changed only through proposals
+                let governor = instance.env().account_id();
+                instance._init_with_admin(governor);
+                // caller can cancel malicious proposals
+                let caller = instance.env().caller();
+                instance._setup_role(CANCELLER, caller);
+                instance.governance_token = governance_token;
+                instance.voting_delay = voting_delay;
+                instance.voting_period = voting_period;
+                instance.quorum_numerator = quorum_numerator;
+                instance.proposal_threshold = proposal_threshold;
+                instance.timelock_delay = timelock_delay;
+            })
+        }
+    }
+}
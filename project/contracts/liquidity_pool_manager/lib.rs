@@ -7,12 +7,15 @@
 pub mod liquidity_pool_manager{
     /// imports of libraries and traits needed
     use brush::contracts::access_control::*;
+    use brush::contracts::access_control::extensions::enumerable::*;
     use brush::contracts::pausable::*;
+    use brush::contracts::traits::ownable::OwnableRef;
     use brush::contracts::traits::psp22::*;
     use brush::contracts::traits::psp34::*;
     use ink_storage::traits::SpreadAllocate;
     use ink_storage::Mapping;
     use ink_lang::codegen::Env;
+    use ink_lang::codegen::EmitEvent;
     use brush::traits::AccountIdExt;
     use brush::traits::ZERO_ADDRESS;
     use ink_lang::ToAccountId;
@@ -23,23 +26,185 @@ pub mod liquidity_pool_manager{
     use liquidity_pool_protocol::traits::loan::LoanRef;
     use liquidity_pool_protocol::traits::loan::LoanInfo;
     use liquidity_pool_protocol::traits::liquidity_pool_manager::*;
+    use liquidity_pool_protocol::traits::safety_module::SafetyModuleRef;
+    use liquidity_pool_protocol::traits::swap_venue::SwapVenueRef;
+    use liquidity_pool_protocol::traits::swap_venue::SwapError;
     use loan_contract::loan::LoanContractRef;
     use btoken_contract::btoken::BTokenContractRef;
 
     /// Constants trait_definition
     /// YEAR in seconds
     pub const YEAR: Timestamp = 31536000;
-    /// Admin role
+    /// Admin role, it is the admin of all other roles and can unpause the protocol
     pub const ADMIN: RoleType = 0;
+    /// Listing admin role, it can list and delist assets, collaterals and change market states
+    pub const LISTING_ADMIN: RoleType = 1;
+    /// Risk admin role, it can change caps, isolation and efficiency mode parameters
+    pub const RISK_ADMIN: RoleType = 2;
+    /// Oracle updater role, it can change conversion rates
+    pub const ORACLE_UPDATER: RoleType = 3;
+    /// Pause guardian role, it can pause but not unpause the protocol
+    pub const PAUSE_GUARDIAN: RoleType = 4;
+    /// Treasury role, it manages protocol reserves
+    pub const TREASURY: RoleType = 5;
     /// default APY
     pub const APY: Balance = 1000;
+    /// default time after maturity when the fixed-term loan can still be repaid before it is liquidatable
+    pub const GRACE_PERIOD: Timestamp = 259200;
+    /// maximal grace period, longer one would keep expired loans open at the fixed rate
+    pub const MAX_GRACE_PERIOD: Timestamp = 2592000;
+    /// default loan to value in percent
+    pub const LTV: Balance = 70;
+    /// default liquidation threshold in percent
+    pub const LIQUIDATION_THRESHOLD: Balance = 75;
+    /// default liquidation bonus in percent
+    pub const LIQUIDATION_BONUS: Balance = 1;
+    /// maximal liquidation bonus in basis points of the debt
+    pub const MAX_LIQUIDATION_BONUS: Balance = 2000;
+    /// category Id of positions without efficiency mode
+    pub const NO_CATEGORY: CategoryId = 0;
+    /// pausable operations
+    pub const LEND: OperationMask = 1 << 0;
+    pub const BORROW: OperationMask = 1 << 1;
+    pub const REPAY: OperationMask = 1 << 2;
+    pub const WITHDRAW: OperationMask = 1 << 3;
+    pub const LIQUIDATION: OperationMask = 1 << 4;
+    pub const FLASHLOAN: OperationMask = 1 << 5;
+    /// denominator of relative parameter changes, 10000 basis points = 100%
+    pub const BASIS_POINTS: Balance = 10000;
+    /// number of withdrawal requests processed by lend, repay and request_withdrawal
+    pub const AUTO_PROCESSED_REQUESTS: u32 = 5;
+    /// default fee of selling collateral with repay_with_collateral in basis points
+    pub const DELEVERAGE_FEE: Balance = 100;
+    /// default slippage of the swap in refinance in basis points
+    pub const REFINANCE_SLIPPAGE: Balance = 100;
+    /// default auction start price in basis points of the oracle price
+    pub const AUCTION_START_PREMIUM: Balance = 12000;
+    /// default auction floor price in basis points of the oracle price
+    pub const AUCTION_FLOOR: Balance = 8000;
+    /// default time in which the auction price declines from the premium to the floor
+    pub const AUCTION_DURATION: Timestamp = 21600;
+    /// operations paused by the circuit breaker, they move assets out of the pool or depend on prices
+    pub const CIRCUIT_BREAKER_OPERATIONS: OperationMask = BORROW | WITHDRAW | LIQUIDATION | FLASHLOAN;
+
+    /// Event emitted when the circuit breaker pauses the market
+    #[ink(event)]
+    pub struct CircuitBreakerTripped {
+        #[ink(topic)]
+        asset: AccountId,
+        reason: CircuitBreakerReason,
+    }
+
+    /// Event emitted when the guardian clears the circuit breaker of the market
+    #[ink(event)]
+    pub struct CircuitBreakerCleared {
+        #[ink(topic)]
+        asset: AccountId,
+        guardian: AccountId,
+    }
+
+    /// Event emitted when the payout of the withdrawal request fails and the request leaves the queue
+    #[ink(event)]
+    pub struct WithdrawalRequestParked {
+        #[ink(topic)]
+        asset: AccountId,
+        #[ink(topic)]
+        owner: AccountId,
+        request_id: WithdrawalRequestId,
+    }
+
+    /// Event emitted when the admin shuts the protocol down
+    #[ink(event)]
+    pub struct ShutdownTriggered {
+        #[ink(topic)]
+        admin: AccountId,
+        timestamp: Timestamp,
+    }
+
+    /// Event emitted when the liquidation starts the auction of the loan collateral
+    #[ink(event)]
+    pub struct AuctionStarted {
+        #[ink(topic)]
+        collateral: AccountId,
+        loan_id: Id,
+        collateral_amount: Balance,
+        debt: Balance,
+        start_value: Balance,
+    }
+
+    /// Event emitted when the bidder buys collateral in the auction
+    #[ink(event)]
+    pub struct AuctionBid {
+        #[ink(topic)]
+        bidder: AccountId,
+        loan_id: Id,
+        collateral_amount: Balance,
+        paid: Balance,
+    }
+
+    /// Event emitted when the auction ends
+    #[ink(event)]
+    pub struct AuctionFinished {
+        loan_id: Id,
+        surplus: Balance,
+        shortfall: Balance,
+    }
+
+    /// Event emitted when collateral seized by the market is sold
+    #[ink(event)]
+    pub struct PoolCollateralSold {
+        #[ink(topic)]
+        asset: AccountId,
+        #[ink(topic)]
+        buyer: AccountId,
+        collateral: AccountId,
+        collateral_amount: Balance,
+        paid: Balance,
+    }
+
+    /// Event emitted when the liquidated loan leaves debt not backed by its collateral
+    #[ink(event)]
+    pub struct BadDebtDetected {
+        #[ink(topic)]
+        asset: AccountId,
+        loan_id: Id,
+        shortfall: Balance,
+    }
+
+    /// Event emitted when the bad debt is covered from protocol reserves
+    #[ink(event)]
+    pub struct BadDebtCovered {
+        #[ink(topic)]
+        asset: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when the bad debt is covered by slashing the safety module
+    #[ink(event)]
+    pub struct BadDebtCoveredBySafetyModule {
+        #[ink(topic)]
+        asset: AccountId,
+        safety_module: AccountId,
+        slashed: Balance,
+        amount: Balance,
+    }
+
+    /// Event emitted when the bad debt is written down from the pool, lowering the btoken exchange rate
+    #[ink(event)]
+    pub struct BadDebtWrittenDown {
+        #[ink(topic)]
+        asset: AccountId,
+        amount: Balance,
+    }
 
     /// Defining storage and its components and also deriving needed imports to our storage.
     #[ink(storage)]
-    #[derive(Default, AccessControlStorage, PausableStorage, SpreadAllocate)]
+    #[derive(Default, AccessControlStorage, AccessControlEnumerableStorage, PausableStorage, SpreadAllocate)]
     pub struct PoolManagerContract {
         #[AccessControlStorageField]
         access: AccessControlData,
+        #[AccessControlEnumerableStorageField]
+        access_enumerable: AccessControlEnumerableData,
         #[PausableStorageField]
         pause: PausableData,
 
@@ -58,10 +223,110 @@ pub mod liquidity_pool_manager{
         pub btoken_asset: Mapping<AccountId, AccountId>,
         /// mapping of collateral_accepted, AccountId of collateral -> bool: accepted/not accepted
         pub collateral_accepted: Mapping<AccountId, bool>,
+        /// Mapping from asset address to the maximum total amount that can be supplied into its pool.
+        /// Assets without an entry have unlimited supply.
+        pub supply_caps: Mapping<AccountId, Balance>,
+        /// Mapping from asset address to the maximum total amount that can be borrowed from its pool.
+        /// Assets without an entry have unlimited borrowing.
+        pub borrow_caps: Mapping<AccountId, Balance>,
+        /// mapping of isolated collaterals, AccountId of collateral -> bool: isolated/not isolated
+        pub isolated_collaterals: Mapping<AccountId, bool>,
+        /// Mapping from (isolated collateral, asset) to the debt ceiling of the asset backed by the collateral.
+        /// The ceiling is in units of the borrowed asset, assets without an entry are not borrowable against the collateral.
+        pub debt_ceilings: Mapping<(AccountId, AccountId), Balance>,
+        /// Mapping from (collateral, asset) to the total debt in the asset of loans backed by the collateral.
+        pub collateral_debt: Mapping<(AccountId, AccountId), Balance>,
+        /// Mapping from category Id to its risk parameters.
+        pub categories: Mapping<CategoryId, AssetCategory>,
+        /// Mapping from asset address to the category it belongs to.
+        pub asset_categories: Mapping<AccountId, CategoryId>,
+        /// Mapping from user address to the category the user opted into.
+        pub user_categories: Mapping<AccountId, CategoryId>,
+        /// Mapping from asset address to the lifecycle state of its market.
+        /// Assets without an entry are active.
+        pub market_states: Mapping<AccountId, MarketState>,
+        /// Mapping from parameter kind to the bounds its setters have to respect.
+        /// Parameters without an entry can be set to any value.
+        pub parameter_bounds: Mapping<ParameterKind, ParameterBounds>,
+        /// Mapping from hash of (parameter kind, target) to the start of the current change window and the value at its start.
+        pub parameter_history: Mapping<[u8; 32], (Timestamp, Option<Balance>)>,
+        /// Mapping from asset address to its circuit breaker configuration.
+        /// Assets without an entry are not protected by the circuit breaker.
+        pub circuit_breakers: Mapping<AccountId, CircuitBreakerConfig>,
+        /// Mapping from (asset, asset_from, asset_to) to the start of the price window and the conversion rate at its start.
+        pub price_references: Mapping<(AccountId, AccountId, AccountId), (Timestamp, Balance)>,
+        /// Mapping from asset address to the block number and the amount which left the pool in that block.
+        pub block_outflows: Mapping<AccountId, (BlockNumber, Balance)>,
+        /// Mapping of assets whose circuit breaker was tripped and not cleared yet.
+        pub tripped_circuit_breakers: Mapping<AccountId, CircuitBreakerReason>,
+        /// Mapping from asset address to the operations paused by its tripped circuit breaker.
+        /// Operations paused by the guardian before the trip are not included.
+        pub circuit_breaker_operations: Mapping<AccountId, OperationMask>,
+        /// Mapping from asset address to its outflow rate limit.
+        /// Assets without an entry have unlimited outflow.
+        pub rate_limits: Mapping<AccountId, RateLimitConfig>,
+        /// Mapping from asset address to the time of the last outflow and the capacity left after it.
+        pub rate_limit_buckets: Mapping<AccountId, (Timestamp, Balance)>,
+        /// Mapping from (asset, request Id) to the withdrawal request waiting for liquidity.
+        /// Canceled and filled requests have no entry.
+        pub withdrawal_requests: Mapping<(AccountId, WithdrawalRequestId), WithdrawalRequest>,
+        /// Mapping from asset address to Id of the first request in its withdrawal queue.
+        pub queue_heads: Mapping<AccountId, WithdrawalRequestId>,
+        /// Mapping from asset address to Id of the next request in its withdrawal queue.
+        pub queue_tails: Mapping<AccountId, WithdrawalRequestId>,
+        /// Mapping from asset address to the number of requests waiting in its withdrawal queue.
+        pub queue_lengths: Mapping<AccountId, u32>,
+        /// Mapping from (asset, request Id) to the request whose payout failed, it waits for the owner to cancel it.
+        pub parked_withdrawal_requests: Mapping<(AccountId, WithdrawalRequestId), WithdrawalRequest>,
+        /// Mapping from (asset, user) to the Balance of btokens the user has escrowed in the withdrawal queue.
+        pub pending_withdrawals: Mapping<(AccountId, AccountId), Balance>,
+        /// Mapping from asset address to Balance of the asset owned by the protocol, it is not part of the pool.
+        pub protocol_reserves: Mapping<AccountId, Balance>,
+        /// Mapping from asset address to bad debt of the market.
+        pub bad_debts: Mapping<AccountId, BadDebt>,
+        /// Mapping from asset address to the safety module insuring the market.
+        pub safety_modules: Mapping<AccountId, AccountId>,
+        /// Timestamp of the shutdown, None while the protocol is running.
+        pub shutdown_timestamp: Option<Timestamp>,
+        /// Mapping from (borrowed asset, collateral) to Balance of collateral seized from loans of the market.
+        /// It belongs to btoken holders of the borrowed asset.
+        pub pool_collateral: Mapping<(AccountId, AccountId), Balance>,
+        /// Mapping from borrowed asset to the list of collaterals seized from loans of the market.
+        pub pool_collateral_tokens: Mapping<AccountId, Vec<AccountId>>,
+        /// Mapping from (borrowed asset, collateral) to Balance of reserve tokens keeping the price the pool paid
+        /// for collateral in repay_with_collateral. They are burned as the collateral leaves the market.
+        pub pool_collateral_claims: Mapping<(AccountId, AccountId), Balance>,
+        /// Mapping from token address to Balance of the token backing loans and auctions or seized by any market.
+        /// It is in the contract, but it is not part of the pool of the token.
+        pub held_collateral: Mapping<AccountId, Balance>,
+        /// Mapping from collateral address to parameters of its liquidation auctions.
+        /// Collaterals without an entry use default parameters.
+        pub auction_configs: Mapping<AccountId, AuctionConfig>,
+        /// Mapping from collateral address to the curve of its liquidation bonus.
+        /// Collaterals without an entry use the flat liquidation bonus of the loan category.
+        pub liquidation_bonus_curves: Mapping<AccountId, LiquidationBonusCurve>,
+        /// Mapping from collateral address to the fee of selling it with repay_with_collateral in basis points.
+        pub deleverage_fees: Mapping<AccountId, Balance>,
+        /// Mapping from (collateral, borrowed asset) to the swap venue selling the collateral.
+        /// Pairs without an entry are bought by the pool.
+        pub swap_venues: Mapping<(AccountId, AccountId), AccountId>,
+        /// Mapping from (sold asset, bought asset) to the slippage refinance accepts on the swap venue in basis points.
+        /// Pairs without an entry use REFINANCE_SLIPPAGE.
+        pub refinance_slippages: Mapping<(AccountId, AccountId), Balance>,
+        /// Mapping from term of fixed-term loans to their APY, terms without an entry are not offered.
+        pub term_rates: Mapping<Timestamp, Balance>,
+        /// Time after maturity when the loan becomes liquidatable, None means GRACE_PERIOD.
+        pub grace_period: Option<Timestamp>,
+        /// Mapping from loan Id to the auction of its collateral.
+        pub auctions: Mapping<Id, Auction>,
+        /// list of loans whose collateral is being auctioned
+        pub active_auctions: Vec<Id>,
     }
 
     /// We inherit the implementation of the access control trait.
     impl AccessControl for PoolManagerContract {}
+    /// We inherit the implementation of the access control enumerable trait.
+    impl AccessControlEnumerable for PoolManagerContract {}
     /// We inherit the implementation of the pausable trait.
     impl Pausable for PoolManagerContract {}
 
@@ -77,8 +342,10 @@ pub mod liquidity_pool_manager{
         /// # Description
         /// lend is an important function which handles the process of lending into the contract
         #[ink(message, payable)]
-        #[modifiers(when_not_paused)]
+        #[modifiers(when_not_paused_op(LEND, Some(asset_address)))]
         fn lend(&mut self, asset_address: AccountId, amount: Balance) -> Result<(), PoolManagerError>{
+            // check if the market accepts new lends
+            self.check_market_active(asset_address)?;
             // get the address of the caller = address of the lender
             let lender = self.env().caller();
             // get the address of the contract
@@ -99,6 +366,12 @@ pub mod liquidity_pool_manager{
             let total_asset = self.get_total_asset(asset_address)?;
             // get the amount of the btoken of the asset
             let total_btoken = self.get_total_btoken(asset_address)?;
+            // check if the lended amount fits under the supply cap
+            if let Some(supply_cap) = self.supply_caps.get(&asset_address) {
+                if total_asset + amount > supply_cap {
+                    return Err(PoolManagerError::SupplyCapExceeded)
+                }
+            }
             // transfer the asset from the user to the contract
             PSP22Ref::transfer_from_builder(&asset_address, lender, contract, amount, Vec::<u8>::new())
                 .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
@@ -120,6 +393,8 @@ pub mod liquidity_pool_manager{
                 .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
                 .fire()
                 .unwrap()?;
+            // new liquidity fills waiting withdrawal requests
+            self.fill_withdrawal_queue(asset_address);
             Ok(())
         }
 
@@ -132,7 +407,8 @@ pub mod liquidity_pool_manager{
         /// * `btoken_amount` - the Balance of how much btoken does he want to withdraw
         ///
         /// # Description
-        /// withdraw is an important function which handles the process of withdrawing asset from the contract
+        /// withdraw is an important function which handles the process of withdrawing asset from the contract.
+        /// Direct withdrawals are rejected while requests wait in the withdrawal queue of the asset.
         #[ink(message)]
         fn withdraw(&mut self, btoken_address: AccountId, btoken_amount: Balance) -> Result<(), PoolManagerError> {
             // save the address of the caller = address of the withdrawer
@@ -141,6 +417,15 @@ pub mod liquidity_pool_manager{
             let contract = self.env().account_id();
             // get asset address from btoken address
             let withdraw_asset = self.get_asset_from_btoken(btoken_address)?;
+            // after shutdown btokens are redeemed through redeem_settlement
+            self.check_not_shutdown()?;
+            // check if the market is not paused
+            self.check_market_not_paused(withdraw_asset)?;
+            check_operation_not_paused(self, WITHDRAW, &Some(withdraw_asset))?;
+            // requests waiting in the queue are filled first
+            if self.get_queue_length(withdraw_asset) > 0 {
+                return Err(PoolManagerError::WithdrawalQueueNotEmpty)
+            }
             // get total_asset = assets in the contract + assets lended
             let total_asset = self.get_total_asset(withdraw_asset)?;
             // get total_supply of btokens
@@ -148,11 +433,15 @@ pub mod liquidity_pool_manager{
             // calculate how much does user able to withdraw
             let withdraw_amount = btoken_amount * total_asset / total_supply;
             // check how much does contract have in the reserve
-            let withdrawable_amount = PSP22Ref::balance_of(&withdraw_asset, contract);
+            let withdrawable_amount = self.get_pool_liquidity(withdraw_asset);
             // if user want to withdraw more than contract has, it is not possible
             if withdraw_amount > withdrawable_amount{
                 return Err(PoolManagerError::InsufficientPoolBalance)
             }
+            // check the outflow rate limit of the pool
+            self.consume_outflow_capacity(withdraw_asset, withdraw_amount)?;
+            // unusual outflow in one block trips the circuit breaker
+            self.record_outflow(withdraw_asset, withdraw_amount);
             // burn btokens
             BTokenRef::burn_builder(&btoken_address, withdrawer, btoken_amount)
                 .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
@@ -166,87 +455,225 @@ pub mod liquidity_pool_manager{
             Ok(())
         }
 
-
         /// # Returns
-        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        /// Returns a WithdrawalRequestId with success and PoolManagerError otherwise
         ///
         /// # Arguments
         /// * `&mut self` - used so we can mutate variables of self component
-        /// * `asset_address` - AccountId of the asset that is borrowed
-        /// * `collateral_address` - AccountId of the asset that is colateralized
-        /// * `amount` - the Balance of how much asset is borrowed
+        /// * `btoken_address` - AccountId of the btoken that user got for lending asset
+        /// * `btoken_amount` - the Balance of how much btoken does he want to withdraw
         ///
         /// # Description
-        /// borrow is an important function which handles the process of borrowing assets from the contract
+        /// request_withdrawal puts the withdrawal to the end of the FIFO queue of the asset, when the pool lacks liquidity.
+        /// The btokens are escrowed by the contract and the request is filled by lend, repay or process_queue
+        /// at the exchange rate of the moment it is filled.
         #[ink(message)]
-        #[modifiers(when_not_paused)]
-        fn borrow(&mut self, asset_address: AccountId, collateral_address: AccountId, amount: Balance) -> Result<(), PoolManagerError> {
-            // get the address of the caller = address of the borrower
-            let borrower = self.env().caller();
+        fn request_withdrawal(&mut self, btoken_address: AccountId, btoken_amount: Balance) -> Result<WithdrawalRequestId, PoolManagerError> {
+            // save the address of the caller = address of the requester
+            let requester = self.env().caller();
             // get the address of the contract
             let contract = self.env().account_id();
-            // get the allowance for the contract
-            let allowance = PSP22Ref::allowance(&collateral_address, borrower, contract);
-            // check if the allowance is sufficient
-            if allowance < amount{
+            // get asset address from btoken address
+            let asset_address = self.get_asset_from_btoken(btoken_address)?;
+            self.check_not_shutdown()?;
+            if btoken_amount == 0 {
+                return Err(PoolManagerError::AmountNotSupported)
+            }
+            // check if the allowance and balance of btokens are sufficient
+            if PSP22Ref::allowance(&btoken_address, requester, contract) < btoken_amount {
                 return Err(PoolManagerError::InsufficientAllowance)
             }
-            let user_balance = PSP22Ref::balance_of(&collateral_address, borrower);
-            // check if the balance is sufficient
-            if user_balance < amount {
+            if PSP22Ref::balance_of(&btoken_address, requester) < btoken_amount {
                 return Err(PoolManagerError::InsufficientBalance)
             }
-            // check if the collateral is accepted
-            if !self.get_collateral_acceptance(collateral_address) {
-                return Err(PoolManagerError::CollateralNotSupported)
-            }
-            // get the btoken asset address
-            let btoken_address = self.get_btoken_from_asset(asset_address)?;
-            // calculate the deposited collateral price
-            let price = self.get_conversion_rate(collateral_address, asset_address, amount);
-            // set the liquidation price to 75%
-            let liquidation_price = price * 75 / 100;
-            // set the borrowed amount to 70%
-            let borrow_amount = price * 70 / 100;
-            // to be sure, check if borrow_ammount is not greater equal to liquidation price
-            if borrow_amount >= liquidation_price {
-                return Err(PoolManagerError::AmountNotSupported)
-            }
-            let asset_balance = PSP22Ref::balance_of(&asset_address, contract);
-            if asset_balance < borrow_amount{
-                return Err(PoolManagerError::InsufficientPoolBalance)
-            }
-            // transfer the collateral to the users address
-            PSP22Ref::transfer_from_builder(&collateral_address, borrower, contract, amount, Vec::<u8>::new())
+            // escrow the btokens in the contract
+            PSP22Ref::transfer_from_builder(&btoken_address, requester, contract, btoken_amount, Vec::<u8>::new())
                 .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
                 .fire()
                 .unwrap()?;
-            // create loan info
-            let loan_info = LoanInfo{
-                borrower: borrower,
-                collateral_token: collateral_address,
-                collateral_amount: amount,
-                borrow_token: asset_address,
-                borrow_amount: borrow_amount,
-                liquidation_price: liquidation_price,
-                timestamp: self.env().block_timestamp(),
-                already_liquidated: false
+            // add the request to the end of the queue
+            let request_id = self.queue_tails.get(&asset_address).unwrap_or(0);
+            self.withdrawal_requests.insert((&asset_address, &request_id), &WithdrawalRequest { owner: requester, btoken_amount });
+            self.queue_tails.insert(&asset_address, &(request_id + 1));
+            self.queue_lengths.insert(&asset_address, &(self.get_queue_length(asset_address) + 1));
+            let pending = self.get_pending_withdrawal(asset_address, requester);
+            self.pending_withdrawals.insert((&asset_address, &requester), &(pending + btoken_amount));
+            // fill the queue with the liquidity the pool has
+            self.fill_withdrawal_queue(asset_address);
+            Ok(request_id)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the asset of the queue
+        /// * `request_id` - Id of the withdrawal request
+        ///
+        /// # Description
+        /// cancel_withdrawal_request removes the not yet filled part of the request from the queue and returns the escrowed btokens.
+        /// Parked requests are canceled the same way.
+        #[ink(message)]
+        fn cancel_withdrawal_request(&mut self, asset_address: AccountId, request_id: WithdrawalRequestId) -> Result<(), PoolManagerError> {
+            // save the address of the caller = address of the requester
+            let requester = self.env().caller();
+            let queued = self.withdrawal_requests.get((&asset_address, &request_id));
+            let request = match queued.clone() {
+                Some(request) => request,
+                None => self.parked_withdrawal_requests.get((&asset_address, &request_id)).ok_or(PoolManagerError::WithdrawalRequestNotFound)?,
             };
-            // create loan
-            LoanRef::create_loan(&(self.loan_account), loan_info)?;
-            // transfer the asset to the borrower
-            PSP22Ref::transfer_builder(&asset_address, borrower, borrow_amount, Vec::<u8>::new())
-                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
-                .fire()
-                .unwrap()?;
-            // mint borrow amount of the reserve token
-            BTokenRef::mint_builder(&btoken_address, contract, borrow_amount)
+            if request.owner != requester {
+                return Err(PoolManagerError::NotTheRequestOwner)
+            }
+            // remove the request, processing skips missing requests
+            if queued.is_some() {
+                self.withdrawal_requests.remove((&asset_address, &request_id));
+                self.queue_lengths.insert(&asset_address, &(self.get_queue_length(asset_address) - 1));
+            } else {
+                self.parked_withdrawal_requests.remove((&asset_address, &request_id));
+            }
+            let pending = self.get_pending_withdrawal(asset_address, requester);
+            self.pending_withdrawals.insert((&asset_address, &requester), &(pending - request.btoken_amount));
+            // return the escrowed btokens
+            let btoken_address = self.get_btoken_from_asset(asset_address)?;
+            PSP22Ref::transfer_builder(&btoken_address, requester, request.btoken_amount, Vec::<u8>::new())
                 .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
                 .fire()
                 .unwrap()?;
             Ok(())
         }
 
+        /// # Returns
+        /// Returns a Balance of paid out asset with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the asset of the queue
+        /// * `max_requests` - maximum number of requests to be processed
+        ///
+        /// # Description
+        /// process_queue is permissionless function filling the withdrawal requests with the liquidity the pool has.
+        /// Requests whose payout fails are parked, so they don't block the queue.
+        #[ink(message)]
+        fn process_queue(&mut self, asset_address: AccountId, max_requests: u32) -> Result<Balance, PoolManagerError> {
+            self.process_withdrawal_queue(asset_address, max_requests)
+        }
+
+        /// # Returns
+        /// Returns an optional WithdrawalRequest, None if the request was filled, canceled or does not exist
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the asset of the queue
+        /// * `request_id` - Id of the withdrawal request
+        ///
+        /// # Description
+        /// get_withdrawal_request is helper function returning the not yet filled part of the request
+        #[ink(message)]
+        fn get_withdrawal_request(&mut self, asset_address: AccountId, request_id: WithdrawalRequestId) -> Option<WithdrawalRequest> {
+            self.withdrawal_requests.get((&asset_address, &request_id))
+        }
+
+        /// # Returns
+        /// Returns an optional WithdrawalRequest, None if the request is not parked
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the asset of the queue
+        /// * `request_id` - Id of the withdrawal request
+        ///
+        /// # Description
+        /// get_parked_withdrawal_request is helper function returning the request whose payout failed, its btokens stay escrowed until it is canceled
+        #[ink(message)]
+        fn get_parked_withdrawal_request(&mut self, asset_address: AccountId, request_id: WithdrawalRequestId) -> Option<WithdrawalRequest> {
+            self.parked_withdrawal_requests.get((&asset_address, &request_id))
+        }
+
+        /// # Returns
+        /// Returns a u32
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the asset of the queue
+        ///
+        /// # Description
+        /// get_queue_length is helper function returning the number of requests waiting in the withdrawal queue
+        #[ink(message)]
+        fn get_queue_length(&mut self, asset_address: AccountId) -> u32 {
+            self.queue_lengths.get(&asset_address).unwrap_or(0)
+        }
+
+        /// # Returns
+        /// Returns an optional u32, None if the request is not in the queue
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the asset of the queue
+        /// * `request_id` - Id of the withdrawal request
+        ///
+        /// # Description
+        /// get_queue_position is helper function returning the number of requests waiting before the request
+        #[ink(message)]
+        fn get_queue_position(&mut self, asset_address: AccountId, request_id: WithdrawalRequestId) -> Option<u32> {
+            self.withdrawal_requests.get((&asset_address, &request_id))?;
+            let head = self.queue_heads.get(&asset_address).unwrap_or(0);
+            // count requests which were not canceled
+            Some((head..request_id).filter(|id| self.withdrawal_requests.get((&asset_address, id)).is_some()).count() as u32)
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the asset of the queue
+        /// * `user` - AccountId of the requester
+        ///
+        /// # Description
+        /// get_pending_withdrawal is helper function returning the Balance of btokens the user has waiting in the queue
+        #[ink(message)]
+        fn get_pending_withdrawal(&mut self, asset_address: AccountId, user: AccountId) -> Balance {
+            self.pending_withdrawals.get((&asset_address, &user)).unwrap_or(0)
+        }
+
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the asset that is borrowed
+        /// * `collateral_address` - AccountId of the asset that is colateralized
+        /// * `amount` - the Balance of how much asset is borrowed
+        ///
+        /// # Description
+        /// borrow is an important function which handles the process of borrowing assets from the contract
+        #[ink(message)]
+        #[modifiers(when_not_paused_op(BORROW, Some(asset_address)))]
+        fn borrow(&mut self, asset_address: AccountId, collateral_address: AccountId, amount: Balance) -> Result<(), PoolManagerError> {
+            self.create_borrow(asset_address, collateral_address, amount, None)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the asset that is borrowed
+        /// * `collateral_address` - AccountId of the asset that is colateralized
+        /// * `amount` - the Balance of how much asset is borrowed
+        /// * `term` - time until maturity of the loan, it has to be offered with set_term_rate
+        ///
+        /// # Description
+        /// borrow_with_term borrows like borrow with the rate of the term. The loan becomes liquidatable
+        /// regardless of price when it is not repaid or rolled over until maturity plus grace period.
+        #[ink(message)]
+        #[modifiers(when_not_paused_op(BORROW, Some(asset_address)))]
+        fn borrow_with_term(&mut self, asset_address: AccountId, collateral_address: AccountId, amount: Balance, term: Timestamp) -> Result<(), PoolManagerError> {
+            self.create_borrow(asset_address, collateral_address, amount, Some(term))
+        }
+
         /// # Returns
         /// Returns a bool with success and PoolManagerError otherwise
         ///
@@ -266,8 +693,16 @@ pub mod liquidity_pool_manager{
             let contract = self.env().account_id();
             // get the loan info
             let loan_info = LoanRef::get_loan_info(&(self.loan_account), loan_id.clone())?;
+            // check if the markets of the loan are not paused
+            self.check_market_not_paused(loan_info.borrow_token)?;
+            self.check_market_not_paused(loan_info.collateral_token)?;
+            check_operation_not_paused(self, REPAY, &Some(loan_info.borrow_token))?;
             // check if loan was not already liquidated, delete the loan in that case
             if loan_info.already_liquidated{
+                // the loan record is needed until its auction ends
+                if self.auctions.get(&loan_id).is_some() {
+                    return Err(PoolManagerError::AuctionActive)
+                }
                 LoanRef::delete_loan(&(self.loan_account), repayer, loan_id.clone())?;
                 return Ok(false)
             }
@@ -283,14 +718,10 @@ pub mod liquidity_pool_manager{
             if  user_balance < repay_amount {
                 return Err(PoolManagerError::InsufficientBalance)
             }
-            // calculate time elapsed since loan creation
-            let timer = self.env().block_timestamp() - loan_info.timestamp;
-            // calculate interest of the loan
-            let interest = (APY * timer as Balance) / YEAR as Balance;
             // calculate how much user needs to repay
-            let to_repay = (((loan_info.borrow_amount) * (10000 + interest)) / 10000) + 1;
-            // get btoken asset addrees
-            let btoken_address = self.get_btoken_from_asset(loan_info.borrow_token)?;
+            let to_repay = self.calculate_debt(&loan_info);
+            // get reserves token address of the borrowed asset
+            let reserves_address = self.get_reserves_from_asset(loan_info.borrow_token)?;
             // case if the user want to repay the whole loan
             if repay_amount >= to_repay {
                 // transfer loaned asset back to the contract
@@ -303,10 +734,13 @@ pub mod liquidity_pool_manager{
                     .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
                     .fire()
                     .unwrap()?;
+                self.release_collateral(loan_info.collateral_token, loan_info.collateral_amount);
                 // delete repayed loan
                 LoanRef::delete_loan(&(self.loan_account), repayer, loan_id)?;
-                // burn btokens
-                BTokenRef::burn_builder(&btoken_address, self.env().caller(), loan_info.borrow_amount)
+                // remove the loan from the debt backed by the collateral
+                self.update_collateral_debt(loan_info.collateral_token, loan_info.borrow_token, loan_info.borrow_amount, 0);
+                // burn the reserve tokens of the repayed loan
+                BTokenRef::burn_builder(&reserves_address, contract, loan_info.borrow_amount)
                     .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
                     .fire()
                     .unwrap()?;
@@ -325,137 +759,419 @@ pub mod liquidity_pool_manager{
                     .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
                     .fire()
                     .unwrap()?;
+                self.release_collateral(loan_info.collateral_token, to_return);
                 // update loan info
                 LoanRef::update_loan(
                     &(self.loan_account),
                     loan_id.clone(),
                     to_repay - repay_amount,
-                    self.env().block_timestamp().into(),
-                    (loan_info.collateral_amount - to_return).try_into().unwrap(),
+                    loan_info.collateral_amount - to_return,
+                    self.env().block_timestamp(),
                 )?;
-                // mint btoken to the contract
-                BTokenRef::mint_builder(&btoken_address, contract, to_repay - repay_amount - loan_info.borrow_amount)
-                    .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
-                    .fire()
-                    .unwrap()?;
+                // align the reserve tokens with the remaining debt of the loan
+                let remaining_debt = to_repay - repay_amount;
+                self.update_collateral_debt(loan_info.collateral_token, loan_info.borrow_token, loan_info.borrow_amount, remaining_debt);
+                if remaining_debt > loan_info.borrow_amount {
+                    BTokenRef::mint_builder(&reserves_address, contract, remaining_debt - loan_info.borrow_amount)
+                        .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                        .fire()
+                        .unwrap()?;
+                }
+                else if remaining_debt < loan_info.borrow_amount {
+                    BTokenRef::burn_builder(&reserves_address, contract, loan_info.borrow_amount - remaining_debt)
+                        .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                        .fire()
+                        .unwrap()?;
+                }
             }
+            // repayed liquidity fills waiting withdrawal requests
+            self.fill_withdrawal_queue(loan_info.borrow_token);
             Ok(true)
         }
 
         /// # Returns
-        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        /// Returns a Balance of repaid debt with success and PoolManagerError otherwise
         ///
         /// # Arguments
         /// * `&mut self` - used so we can mutate variables of self component
-        /// * `loan_id` - Id of the loan to be liquidated
+        /// * `loan_id` - Id of the loan to be repaid
+        /// * `collateral_amount` - Balance of the loan collateral to be sold
         ///
         /// # Description
-        /// liquidate is an important function which handles the process of liquidating unhealthy assets
+        /// repay_with_collateral lets the owner of the loan repay it without the borrowed asset.
+        /// Part of the loan collateral is sold at the oracle price minus the deleverage fee,
+        /// on the swap venue of the pair or to the pool, and the proceeds repay the debt.
+        /// Only collateral worth the debt is sold, the whole collateral is returned when the debt is repaid.
         #[ink(message)]
-        fn liquidate_loan(&mut self, loan_id: Id) -> Result<(), PoolManagerError> {
-            // get the address of the caller = address of the liquidator
-            let liquidator = self.env().caller();
-            // get the loan account
+        fn repay_with_collateral(&mut self, loan_id: Id, collateral_amount: Balance) -> Result<Balance, PoolManagerError> {
+            // get the address of the caller = address of the loan owner
+            let owner = self.env().caller();
+            // get the address of the contract
+            let contract = self.env().account_id();
             let loan_account = self.loan_account;
-            // get loan info of the loan id
             let loan_info = LoanRef::get_loan_info(&loan_account, loan_id.clone())?;
-            // if the loan is already liquidated, return error
+            if LoanRef::owner_of(&loan_account, loan_id.clone()) != Some(owner) {
+                return Err(PoolManagerError::NotTheLoanOwner)
+            }
             if loan_info.already_liquidated {
                 return Err(PoolManagerError::LoanAlreadyLiquidated)
             }
-            // get collateral price
-            let price = self.get_conversion_rate(loan_info.collateral_token, loan_info.borrow_token, loan_info.collateral_amount);
-            // check if the loan is unhealthy
-            if price <= loan_info.liquidation_price {
-                // calculate reward for liquidating
-                let reward = (loan_info.collateral_amount * 1000) / 100000;
-                // transfer collateral to liquidator
-                PSP22Ref::transfer_builder(&loan_info.collateral_token, liquidator, reward,Vec::<u8>::new())
+            // after shutdown loans are closed through settle_loan
+            self.check_not_shutdown()?;
+            // check if the markets of the loan are not paused
+            self.check_market_not_paused(loan_info.borrow_token)?;
+            self.check_market_not_paused(loan_info.collateral_token)?;
+            check_operation_not_paused(self, REPAY, &Some(loan_info.borrow_token))?;
+            if collateral_amount == 0 || collateral_amount > loan_info.collateral_amount {
+                return Err(PoolManagerError::AmountNotSupported)
+            }
+            // value the collateral at the oracle price minus the fee, do not sell more than the debt is worth
+            let category = self.get_risk_parameters(loan_info.category);
+            let debt = self.calculate_debt(&loan_info);
+            let fee = self.get_deleverage_fee(loan_info.collateral_token);
+            let mut amount = collateral_amount;
+            let mut min_proceeds = self.get_collateral_value(loan_info.collateral_token, loan_info.borrow_token, amount, &category) * (BASIS_POINTS - fee) / BASIS_POINTS;
+            if min_proceeds > debt {
+                amount = amount * debt / min_proceeds;
+                min_proceeds = debt;
+            }
+            if amount == 0 || min_proceeds == 0 {
+                return Err(PoolManagerError::AmountNotSupported)
+            }
+            let reserves_address = self.get_reserves_from_asset(loan_info.borrow_token)?;
+            // sell the collateral
+            let (repaid, pool_claim) = match self.get_swap_venue(loan_info.collateral_token, loan_info.borrow_token) {
+                Some(swap_venue) => {
+                    let balance = PSP22Ref::balance_of(&loan_info.borrow_token, contract);
+                    PSP22Ref::approve_builder(&loan_info.collateral_token, swap_venue, amount)
+                        .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                        .fire()
+                        .unwrap()?;
+                    SwapVenueRef::swap_builder(&swap_venue, loan_info.collateral_token, loan_info.borrow_token, amount, min_proceeds)
+                        .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                        .fire()
+                        .unwrap()?;
+                    self.release_collateral(loan_info.collateral_token, amount);
+                    // check what the venue really paid
+                    let proceeds = PSP22Ref::balance_of(&loan_info.borrow_token, contract) - balance;
+                    if proceeds < min_proceeds {
+                        return Err(PoolManagerError::SwapError(SwapError::InsufficientOutputAmount))
+                    }
+                    // proceeds above the debt belong to the owner
+                    if proceeds > debt {
+                        PSP22Ref::transfer_builder(&loan_info.borrow_token, owner, proceeds - debt, Vec::<u8>::new())
+                            .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                            .fire()
+                            .unwrap()?;
+                    }
+                    (core::cmp::min(proceeds, debt), 0)
+                }
+                None => {
+                    // the pool buys the collateral, its reserve tokens keep the price paid until the collateral is sold
+                    self.add_pool_collateral(loan_info.borrow_token, loan_info.collateral_token, amount);
+                    let claim = self.get_pool_collateral_claim(loan_info.borrow_token, loan_info.collateral_token);
+                    self.pool_collateral_claims.insert((&loan_info.borrow_token, &loan_info.collateral_token), &(claim + min_proceeds));
+                    (min_proceeds, min_proceeds)
+                }
+            };
+            let remaining_debt = debt - repaid;
+            let remaining_collateral = loan_info.collateral_amount - amount;
+            self.update_collateral_debt(loan_info.collateral_token, loan_info.borrow_token, loan_info.borrow_amount, remaining_debt);
+            // align the reserve tokens with the remaining debt and the collateral bought by the pool
+            let reserves = remaining_debt + pool_claim;
+            if reserves > loan_info.borrow_amount {
+                BTokenRef::mint_builder(&reserves_address, contract, reserves - loan_info.borrow_amount)
                     .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
                     .fire()
                     .unwrap()?;
-                // use liquidation loan function from Loan
-                LoanRef::liquidate_loan(&loan_account, loan_id.clone())?;
             }
-            else {
-                return Err(PoolManagerError::LoanUnliquidable)
+            else if reserves < loan_info.borrow_amount {
+                BTokenRef::burn_builder(&reserves_address, contract, loan_info.borrow_amount - reserves)
+                    .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                    .fire()
+                    .unwrap()?;
             }
-        Ok(())
+            if remaining_debt == 0 {
+                // the loan is repaid, return the rest of the collateral and delete the loan
+                if remaining_collateral > 0 {
+                    PSP22Ref::transfer_builder(&loan_info.collateral_token, owner, remaining_collateral, Vec::<u8>::new())
+                        .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                        .fire()
+                        .unwrap()?;
+                    self.release_collateral(loan_info.collateral_token, remaining_collateral);
+                }
+                LoanRef::delete_loan(&loan_account, owner, loan_id)?;
+            } else {
+                LoanRef::update_loan(&loan_account, loan_id, remaining_debt, remaining_collateral, self.env().block_timestamp())?;
+            }
+            // repaid liquidity fills waiting withdrawal requests
+            self.fill_withdrawal_queue(loan_info.borrow_token);
+            Ok(repaid)
         }
 
         /// # Returns
-        /// Returns a bool
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
         ///
         /// # Arguments
         /// * `&mut self` - used so we can mutate variables of self component
-        /// * `asset_address` - AccountId of asset we want to know if it is lendable to the contract.
+        /// * `loan_id` - Id of the loan
+        /// * `new_collateral` - AccountId of the new collateral
+        /// * `amount` - Balance of the new collateral deposited by the caller
         ///
         /// # Description
-        /// get_asset_acceptance is helper function
-        /// It is responsible for getting bool representation of acceptance of the specific asset.
+        /// swap_collateral lets the owner of the loan replace its collateral, the old collateral is returned.
+        /// The new collateral has to back the debt with interest at the loan to value of the loan category
+        /// and the liquidation price is recomputed from it. The loan keeps its Id. It is paused together with borrows.
         #[ink(message)]
-        fn get_asset_acceptance(&mut self, asset_address: AccountId) -> bool {
-            let acceptance = !self.asset_btoken.get(&asset_address).unwrap_or(ZERO_ADDRESS.into()).is_zero();
-            acceptance
+        fn swap_collateral(&mut self, loan_id: Id, new_collateral: AccountId, amount: Balance) -> Result<(), PoolManagerError> {
+            // get the address of the caller = address of the loan owner
+            let owner = self.env().caller();
+            // get the address of the contract
+            let contract = self.env().account_id();
+            let loan_account = self.loan_account;
+            let loan_info = LoanRef::get_loan_info(&loan_account, loan_id.clone())?;
+            if LoanRef::owner_of(&loan_account, loan_id.clone()) != Some(owner) {
+                return Err(PoolManagerError::NotTheLoanOwner)
+            }
+            if loan_info.already_liquidated {
+                return Err(PoolManagerError::LoanAlreadyLiquidated)
+            }
+            // after shutdown loans are closed through settle_loan
+            self.check_not_shutdown()?;
+            // the old collateral can leave a market which is not paused, the new one has to accept new borrows
+            self.check_market_not_paused(loan_info.borrow_token)?;
+            self.check_market_not_paused(loan_info.collateral_token)?;
+            self.check_market_active(new_collateral)?;
+            // the new collateral backs the debt like a new borrow
+            check_operation_not_paused(self, BORROW, &Some(loan_info.borrow_token))?;
+            if amount == 0 {
+                return Err(PoolManagerError::AmountNotSupported)
+            }
+            if !self.get_collateral_acceptance(new_collateral) {
+                return Err(PoolManagerError::CollateralNotSupported)
+            }
+            // check if the allowance and balance of the new collateral are sufficient
+            if PSP22Ref::allowance(&new_collateral, owner, contract) < amount {
+                return Err(PoolManagerError::InsufficientAllowance)
+            }
+            if PSP22Ref::balance_of(&new_collateral, owner) < amount {
+                return Err(PoolManagerError::InsufficientBalance)
+            }
+            // loans in efficiency mode can only use assets from their category
+            if loan_info.category != NO_CATEGORY && self.get_asset_category(new_collateral) != loan_info.category {
+                return Err(PoolManagerError::AssetNotInCategory)
+            }
+            // the new collateral has to back the debt at the loan to value
+            let category = self.get_risk_parameters(loan_info.category);
+            let price = self.get_collateral_value(new_collateral, loan_info.borrow_token, amount, &category);
+            if self.calculate_debt(&loan_info) > price * category.ltv / 100 {
+                return Err(PoolManagerError::InsufficientCollateral)
+            }
+            // isolated collateral can only back configured assets up to its debt ceiling
+            if self.is_collateral_isolated(new_collateral) {
+                let debt_ceiling = self.get_debt_ceiling(new_collateral, loan_info.borrow_token)
+                    .ok_or(PoolManagerError::AssetNotBorrowableInIsolation)?;
+                let collateral_debt = self.get_collateral_debt(new_collateral, loan_info.borrow_token);
+                let collateral_debt = if new_collateral == loan_info.collateral_token {
+                    collateral_debt.saturating_sub(loan_info.borrow_amount)
+                } else {
+                    collateral_debt
+                };
+                if collateral_debt + loan_info.borrow_amount > debt_ceiling {
+                    return Err(PoolManagerError::DebtCeilingExceeded)
+                }
+            }
+            // exchange the collateral
+            PSP22Ref::transfer_from_builder(&new_collateral, owner, contract, amount, Vec::<u8>::new())
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            PSP22Ref::transfer_builder(&loan_info.collateral_token, owner, loan_info.collateral_amount, Vec::<u8>::new())
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            self.hold_collateral(new_collateral, amount);
+            self.release_collateral(loan_info.collateral_token, loan_info.collateral_amount);
+            // move the loan to the debt backed by the new collateral
+            self.update_collateral_debt(loan_info.collateral_token, loan_info.borrow_token, loan_info.borrow_amount, 0);
+            self.update_collateral_debt(new_collateral, loan_info.borrow_token, 0, loan_info.borrow_amount);
+            // update the loan, it keeps its Id and owner
+            let new_loan_info = LoanInfo {
+                collateral_token: new_collateral,
+                collateral_amount: amount,
+                liquidation_price: price * category.liquidation_threshold / 100,
+                ..loan_info
+            };
+            LoanRef::set_loan_info(&loan_account, loan_id, new_loan_info)?;
+            Ok(())
         }
 
         /// # Returns
-        /// Returns a bool
+        /// Returns a Balance of the new borrowed amount with success and PoolManagerError otherwise
         ///
         /// # Arguments
         /// * `&mut self` - used so we can mutate variables of self component
-        /// * `asset_address` - AccountId of asset we want to know if it is collateralizable
+        /// * `loan_id` - Id of the loan
+        /// * `new_borrow_asset` - AccountId of the asset the loan is moved to
         ///
         /// # Description
-        /// get_collateral_acceptance is helper function
-        /// It is responsible for getting bool representation of acceptance of the specific collateral asset.
+        /// refinance lets the owner of the loan move its open-ended debt to another asset, the loan keeps its Id and collateral.
+        /// The new asset is borrowed from its pool and sold on the swap venue of the pair for the old asset,
+        /// which repays the old debt with interest. The owner borrows the refinance slippage of the pair more than the oracle value
+        /// of the debt and the old asset received above the debt is returned to the owner. Fixed-term loans are rolled over instead.
         #[ink(message)]
-        fn get_collateral_acceptance(&mut self, asset_address: AccountId) -> bool {
-            self.collateral_accepted.get(&asset_address).unwrap_or(false)
-        }
-
-        /// # Returns
-        /// Returns a Balance with success and PoolManagerError otherwise
-        ///
+        #[modifiers(when_not_paused_op(BORROW, Some(new_borrow_asset)))]
+        fn refinance(&mut self, loan_id: Id, new_borrow_asset: AccountId) -> Result<Balance, PoolManagerError> {
+            // get the address of the caller = address of the loan owner
+            let owner = self.env().caller();
+            // get the address of the contract
+            let contract = self.env().account_id();
+            let loan_account = self.loan_account;
+            let loan_info = LoanRef::get_loan_info(&loan_account, loan_id.clone())?;
+            if LoanRef::owner_of(&loan_account, loan_id.clone()) != Some(owner) {
+                return Err(PoolManagerError::NotTheLoanOwner)
+            }
+            if loan_info.already_liquidated {
+                return Err(PoolManagerError::LoanAlreadyLiquidated)
+            }
+            if new_borrow_asset == loan_info.borrow_token {
+                return Err(PoolManagerError::AmountNotSupported)
+            }
+            // the term and its rate belong to the borrowed asset, fixed-term loans can't change it
+            if loan_info.maturity.is_some() {
+                return Err(PoolManagerError::FixedTermLoan)
+            }
+            // the old debt is repaid and the new market has to accept new borrows
+            self.check_market_not_paused(loan_info.borrow_token)?;
+            self.check_market_not_paused(loan_info.collateral_token)?;
+            check_operation_not_paused(self, REPAY, &Some(loan_info.borrow_token))?;
+            self.check_market_active(new_borrow_asset)?;
+            let swap_venue = self.get_swap_venue(new_borrow_asset, loan_info.borrow_token).ok_or(PoolManagerError::SwapVenueNotFound)?;
+            // loans in efficiency mode can only use assets from their category
+            if loan_info.category != NO_CATEGORY && self.get_asset_category(new_borrow_asset) != loan_info.category {
+                return Err(PoolManagerError::AssetNotInCategory)
+            }
+            // calculate the old debt with interest the same way as repay
+            let category = self.get_risk_parameters(loan_info.category);
+            let debt = self.calculate_debt(&loan_info);
+            // borrow enough of the new asset to buy the old debt on the swap venue
+            let debt_value = self.get_collateral_value(loan_info.borrow_token, new_borrow_asset, debt, &category);
+            let slippage = self.get_refinance_slippage(new_borrow_asset, loan_info.borrow_token);
+            let borrow_amount = debt_value * BASIS_POINTS / (BASIS_POINTS - slippage) + 1;
+            // the collateral has to back the new debt at the loan to value
+            let price = self.get_collateral_value(loan_info.collateral_token, new_borrow_asset, loan_info.collateral_amount, &category);
+            if borrow_amount > price * category.ltv / 100 {
+                return Err(PoolManagerError::InsufficientCollateral)
+            }
+            if self.get_pool_liquidity(new_borrow_asset) < borrow_amount {
+                return Err(PoolManagerError::InsufficientPoolBalance)
+            }
+            // check if the borrowed amount fits under the borrow cap
+            if let Some(borrow_cap) = self.borrow_caps.get(&new_borrow_asset) {
+                if self.get_total_borrowed(new_borrow_asset)? + borrow_amount > borrow_cap {
+                    return Err(PoolManagerError::BorrowCapExceeded)
+                }
+            }
+            // isolated collateral can only back configured assets up to its debt ceiling
+            if self.is_collateral_isolated(loan_info.collateral_token) {
+                let debt_ceiling = self.get_debt_ceiling(loan_info.collateral_token, new_borrow_asset)
+                    .ok_or(PoolManagerError::AssetNotBorrowableInIsolation)?;
+                if self.get_collateral_debt(loan_info.collateral_token, new_borrow_asset) + borrow_amount > debt_ceiling {
+                    return Err(PoolManagerError::DebtCeilingExceeded)
+                }
+            }
+            // check the outflow rate limit of the new pool
+            self.consume_outflow_capacity(new_borrow_asset, borrow_amount)?;
+            // unusual outflow in one block trips the circuit breaker
+            self.record_outflow(new_borrow_asset, borrow_amount);
+            // sell the new asset for the old one
+            let balance = PSP22Ref::balance_of(&loan_info.borrow_token, contract);
+            PSP22Ref::approve_builder(&new_borrow_asset, swap_venue, borrow_amount)
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            SwapVenueRef::swap_builder(&swap_venue, new_borrow_asset, loan_info.borrow_token, borrow_amount, debt)
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            // check what the venue really paid
+            let proceeds = PSP22Ref::balance_of(&loan_info.borrow_token, contract) - balance;
+            if proceeds < debt {
+                return Err(PoolManagerError::SwapError(SwapError::InsufficientOutputAmount))
+            }
+            if proceeds > debt {
+                PSP22Ref::transfer_builder(&loan_info.borrow_token, owner, proceeds - debt, Vec::<u8>::new())
+                    .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                    .fire()
+                    .unwrap()?;
+            }
+            // the old pool got its debt with interest back, burn the reserve tokens of the loan
+            let old_reserves_address = self.get_reserves_from_asset(loan_info.borrow_token)?;
+            BTokenRef::burn_builder(&old_reserves_address, contract, loan_info.borrow_amount)
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            // the new pool lended the borrowed amount, mint its reserve tokens
+            let new_reserves_address = self.get_reserves_from_asset(new_borrow_asset)?;
+            BTokenRef::mint_builder(&new_reserves_address, contract, borrow_amount)
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            self.update_collateral_debt(loan_info.collateral_token, loan_info.borrow_token, loan_info.borrow_amount, 0);
+            self.update_collateral_debt(loan_info.collateral_token, new_borrow_asset, 0, borrow_amount);
+            // rewrite the loan, it keeps its Id, owner and collateral
+            let old_borrow_token = loan_info.borrow_token;
+            let new_loan_info = LoanInfo {
+                borrow_token: new_borrow_asset,
+                borrow_amount,
+                liquidation_price: price * category.liquidation_threshold / 100,
+                timestamp: self.env().block_timestamp(),
+                interest_rate: APY,
+                ..loan_info
+            };
+            LoanRef::set_loan_info(&loan_account, loan_id, new_loan_info)?;
+            // repaid liquidity fills waiting withdrawal requests of the old pool
+            self.fill_withdrawal_queue(old_borrow_token);
+            Ok(borrow_amount)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
         /// # Arguments
         /// * `&mut self` - used so we can mutate variables of self component
-        /// * `asset_address` - AccountId of asset from which we want to get its total Balance
+        /// * `sold_asset` - AccountId of the asset the loan is moved to, it is sold on the swap venue
+        /// * `bought_asset` - AccountId of the asset the loan is moved from
+        /// * `slippage` - slippage in basis points
         ///
         /// # Description
-        /// get_total_asset is helper function
-        /// It is responsible for getting total asset Balance for given asset addresss (lended + in pool).
+        /// set_refinance_slippage is RISK_ADMIN function to change how much more than the oracle value refinance borrows for the swap
         #[ink(message)]
-        fn get_total_asset(&mut self, asset_address: AccountId) -> Result<Balance, PoolManagerError> {
-            // get asset lended from asset address
-            let asset_lended = self.assets_lended.get(&asset_address).unwrap_or(ZERO_ADDRESS.into());
-            // check if we got something
-            if asset_lended.is_zero() {
-                return Err(PoolManagerError::AssetNotFound)
+        #[modifiers(only_role(RISK_ADMIN))]
+        fn set_refinance_slippage(&mut self, sold_asset: AccountId, bought_asset: AccountId, slippage: Balance) -> Result<(), PoolManagerError> {
+            if slippage >= BASIS_POINTS {
+                return Err(PoolManagerError::InvalidSlippage)
             }
-            // get AccountId of executed contract
-            let contract = self.env().account_id();
-            // calculate assets in contract
-            let available = PSP22Ref::balance_of(&asset_address, contract);
-            // calculate assets lended
-            let unavailable = PSP22Ref::balance_of(&asset_lended, contract);
-            Ok(available + unavailable)
+            // check the slippage against its bounds
+            let previous_slippage = self.get_refinance_slippage(sold_asset, bought_asset);
+            self.check_parameter_change(ParameterKind::RefinanceSlippage, &(sold_asset, bought_asset), Some(previous_slippage), Some(slippage))?;
+            self.refinance_slippages.insert((&sold_asset, &bought_asset), &slippage);
+            Ok(())
         }
 
         /// # Returns
-        /// Returns a Balance with success and PoolManagerError otherwise
+        /// Returns a Balance in basis points
         ///
         /// # Arguments
         /// * `&mut self` - used so we can mutate variables of self component
-        /// * `asset_address` - AccountId of asset from which we want to get btoken bounded total Balance
+        /// * `sold_asset` - AccountId of the asset the loan is moved to
+        /// * `bought_asset` - AccountId of the asset the loan is moved from
         ///
         /// # Description
-        /// get_total_btoken is helper function
-        /// It is responsible for getting total Balance for given pool addresss.
+        /// get_refinance_slippage is helper function returning the slippage refinance accepts on the swap venue of the pair
         #[ink(message)]
-        fn get_total_btoken(&mut self, asset_address: AccountId) -> Result<Balance, PoolManagerError>{
-            let btoken_address = self.get_btoken_from_asset(asset_address)?;
-            let total = PSP22Ref::total_supply(&btoken_address);
-            Ok(total)
+        fn get_refinance_slippage(&mut self, sold_asset: AccountId, bought_asset: AccountId) -> Balance {
+            self.refinance_slippages.get((&sold_asset, &bought_asset)).unwrap_or(REFINANCE_SLIPPAGE)
         }
 
         /// # Returns
@@ -463,226 +1179,1987 @@ pub mod liquidity_pool_manager{
         ///
         /// # Arguments
         /// * `&mut self` - used so we can mutate variables of self component
-        /// * `asset_address` - AccountId of asset to be allowed
-        /// * `name` optional String for specifying our shares name in Metadata
-        /// * `symbol` optional String for specifying our shares symbol in Metadata
-        /// * `decimal` u8 for specifying our shares decimals in Metadata
+        /// * `collateral_address` - AccountId of the collateral
+        /// * `fee` - fee in basis points
         ///
         /// # Description
-        /// set_asset_allowance is ADMIN function to make asset lendable and borrowable
-        #[ink(message, payable)]
-        #[modifiers(only_role(ADMIN))]
-        fn set_asset_allowance(&mut self, asset_address: AccountId) -> Result<(), PoolManagerError> {
-            // check for asset allowance
-            if self.get_asset_acceptance(asset_address) {
-                return Err(PoolManagerError::AssetAlreadySupported)
+        /// set_deleverage_fee is RISK_ADMIN function to change the discount of the collateral sold with repay_with_collateral
+        #[ink(message)]
+        #[modifiers(only_role(RISK_ADMIN))]
+        fn set_deleverage_fee(&mut self, collateral_address: AccountId, fee: Balance) -> Result<(), PoolManagerError> {
+            if fee >= BASIS_POINTS {
+                return Err(PoolManagerError::InvalidDeleverageFee)
             }
-            // instantiate the btoken for lended assets and pool token for reserves
-            let btoken_address = self.instantiate_btoken_contract("BTokenShares", "BTKS");
-            let reserves_address = self.instantiate_btoken_contract("TokenReserves", "TRS");
-            // accept the asset by inserting it into the mappings
-            self.assets_lended.insert(&asset_address, &reserves_address);
-            self.asset_btoken.insert(&asset_address, &btoken_address);
-            self.btoken_asset.insert(&btoken_address, &asset_address);
+            // check the fee against its bounds
+            let previous_fee = self.get_deleverage_fee(collateral_address);
+            self.check_parameter_change(ParameterKind::DeleverageFee, &collateral_address, Some(previous_fee), Some(fee))?;
+            self.deleverage_fees.insert(&collateral_address, &fee);
             Ok(())
         }
 
+        /// # Returns
+        /// Returns a Balance in basis points
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `collateral_address` - AccountId of the collateral
+        ///
+        /// # Description
+        /// get_deleverage_fee is helper function returning the fee of selling the collateral with repay_with_collateral
+        #[ink(message)]
+        fn get_deleverage_fee(&mut self, collateral_address: AccountId) -> Balance {
+            self.deleverage_fees.get(&collateral_address).unwrap_or(DELEVERAGE_FEE)
+        }
+
         /// # Returns
         /// Returns a Ok(()) with success and PoolManagerError otherwise
         ///
         /// # Arguments
         /// * `&mut self` - used so we can mutate variables of self component
-        /// * `asset_address` - AccountId of asset to be allowed as a collateral
+        /// * `collateral_address` - AccountId of the sold collateral
+        /// * `asset_address` - AccountId of the borrowed asset
+        /// * `swap_venue` - optional AccountId of the swap venue, None lets the pool buy the collateral
         ///
         /// # Description
-        /// set_collateral_allowance is ADMIN function to make asset collateralizable for the loans
+        /// set_swap_venue is ADMIN function to choose where repay_with_collateral sells the collateral
         #[ink(message)]
         #[modifiers(only_role(ADMIN))]
-        fn set_collateral_allowance(&mut self, asset_address: AccountId) -> Result<(), PoolManagerError> {
-            // check for collateral allowance
-            if self.get_collateral_acceptance(asset_address) {
-                return Err(PoolManagerError::CollateralAlreadySupported)
+        fn set_swap_venue(&mut self, collateral_address: AccountId, asset_address: AccountId, swap_venue: Option<AccountId>) -> Result<(), PoolManagerError> {
+            match swap_venue {
+                Some(swap_venue) => self.swap_venues.insert((&collateral_address, &asset_address), &swap_venue),
+                None => self.swap_venues.remove((&collateral_address, &asset_address)),
             }
-            // accept the collateral by inserting it into the mapping
-            self.collateral_accepted.insert(&asset_address, &true);
             Ok(())
         }
 
+        /// # Returns
+        /// Returns an optional AccountId, None if the pool buys the collateral
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `collateral_address` - AccountId of the sold collateral
+        /// * `asset_address` - AccountId of the borrowed asset
+        ///
+        /// # Description
+        /// get_swap_venue is helper function returning the swap venue of the pair
+        #[ink(message)]
+        fn get_swap_venue(&mut self, collateral_address: AccountId, asset_address: AccountId) -> Option<AccountId> {
+            self.swap_venues.get((&collateral_address, &asset_address))
+        }
+
         /// # Returns
         /// Returns a Ok(()) with success and PoolManagerError otherwise
         ///
         /// # Arguments
         /// * `&mut self` - used so we can mutate variables of self component
-        /// * `asset_address` - AccountId of asset to be disallowed
+        /// * `loan_id` - Id of the loan to be liquidated
         ///
         /// # Description
-        /// set_asset_disallowance is ADMIN function to make asset unlendable
+        /// liquidate is an important function which handles the process of liquidating unhealthy assets
         #[ink(message)]
-        #[modifiers(only_role(ADMIN))]
-        fn set_asset_disallowance(&mut self, asset_address: AccountId) -> Result<(), PoolManagerError> {
-            // obtain btoken address
-            let btoken_address = self.get_btoken_from_asset(asset_address)?;
-            // get btoken status and check for pool emptiness
-            let asset_balance = PSP22Ref::balance_of(&asset_address, self.env().account_id());
-            let btoken_balance = PSP22Ref::balance_of(&btoken_address, self.env().account_id());
-            if asset_balance > 0 || btoken_balance > 0{
-                return Err(PoolManagerError::PoolIsNotEmpty)
+        fn liquidate_loan(&mut self, loan_id: Id) -> Result<(), PoolManagerError> {
+            let liquidator = self.env().caller();
+            self.liquidate(liquidator, loan_id)
+        }
+
+        /// # Returns
+        /// Returns a list with LiquidationResult of every loan, the outcome of the liquidation and of the purchase
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `liquidations` - list of pairs (loan Id, maximum Balance of the borrowed asset the caller pays in the auction)
+        ///
+        /// # Description
+        /// liquidate_batch is keeper function liquidating multiple loans in one call.
+        /// Loans which are healthy or already liquidated are skipped with their error, the other loans are still processed.
+        /// With non zero max_repay the caller also buys collateral in the started auction at its start price,
+        /// a failed purchase doesn't undo the liquidation.
+        #[ink(message)]
+        fn liquidate_batch(&mut self, liquidations: Vec<(Id, Balance)>) -> Vec<LiquidationResult> {
+            // get the address of the caller = address of the liquidator
+            let liquidator = self.env().caller();
+            let mut results = Vec::new();
+            for (loan_id, max_repay) in liquidations {
+                results.push(self.liquidate_and_buy(liquidator, loan_id, max_repay));
             }
-            self.assets_lended.remove(&asset_address);
-            self.asset_btoken.remove(&asset_address);
-            self.btoken_asset.remove(&btoken_address);
-            Ok(())
+            results
         }
 
         /// # Returns
-        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        /// Returns a list of loan Ids
         ///
         /// # Arguments
         /// * `&mut self` - used so we can mutate variables of self component
-        /// * `asset_address` - AccountId of asset to be disallowed as a collateral
+        /// * `offset` - number of loan Ids to skip
+        /// * `limit` - maximum number of loan Ids to scan
         ///
         /// # Description
-        /// set_collateral_disallowance is ADMIN function to make asset uncollateralizable
+        /// find_liquidatable is helper function for keepers scanning the loan list of the Loan contract
+        /// and returning loans which can be liquidated now
         #[ink(message)]
-        #[modifiers(only_role(ADMIN))]
-        fn set_collateral_disallowance(&mut self, asset_address: AccountId) -> Result<(), PoolManagerError> {
-            // check for collateral disallowance
-            if !self.get_collateral_acceptance(asset_address) {
-                return Err(PoolManagerError::CollateralAlreadyUnsupported)
+        fn find_liquidatable(&mut self, offset: u32, limit: u32) -> Vec<Id> {
+            let mut liquidatable = Vec::new();
+            for (loan_id, loan_info) in LoanRef::get_loan_list(&(self.loan_account), offset, limit) {
+                if loan_info.already_liquidated {
+                    continue
+                }
+                let category = self.get_risk_parameters(loan_info.category);
+                if self.calculate_health(&loan_info, &category) < BASIS_POINTS || self.is_loan_expired(&loan_info) {
+                    liquidatable.push(loan_id);
+                }
             }
-            // disallow the collateral by setting its value to false
-            self.collateral_accepted.insert(&asset_address, &false);
-            Ok(())
+            liquidatable
         }
 
         /// # Returns
-        /// Without a return value
+        /// Returns a Balance of the paid interest with success and PoolManagerError otherwise
         ///
         /// # Arguments
         /// * `&mut self` - used so we can mutate variables of self component
-        /// * `asset_from` - AccountId of source asset from which the conversion takes place
-        /// * `asset_to` - AccountId of destionation asset to which the conversion takes place
-        /// * `exchange_rate` - Balance of 1 asset_from to X asset_to conversion
+        /// * `loan_id` - Id of the loan
+        /// * `term` - time until the new maturity of the loan, it has to be offered with set_term_rate
         ///
         /// # Description
-        /// set_conversion_rate function is helper function responsible for setting conversion rates between assets
-        /// It says us how many of asset_to assets do we get for 1 asset_from asset.
+        /// roll_over lets the owner of the loan extend it by repaying its accrued interest.
+        /// The loan keeps its principal and collateral, it starts accruing again from now at the rate of the term.
+        /// Only fixed-term loans are rolled over, open-ended loans choose their term at borrow.
         #[ink(message)]
-        #[modifiers(only_role(ADMIN))]
-        fn set_conversion_rate(&mut self, asset_from: AccountId, asset_to: AccountId, exchange_rate: Balance) -> Result<(), PoolManagerError> {
-            self.conversion_rates.insert((&asset_from, &asset_to), &exchange_rate);
-            Ok(())
+        fn roll_over(&mut self, loan_id: Id, term: Timestamp) -> Result<Balance, PoolManagerError> {
+            // get the address of the caller = address of the loan owner
+            let owner = self.env().caller();
+            // get the address of the contract
+            let contract = self.env().account_id();
+            let loan_account = self.loan_account;
+            let loan_info = LoanRef::get_loan_info(&loan_account, loan_id.clone())?;
+            if LoanRef::owner_of(&loan_account, loan_id.clone()) != Some(owner) {
+                return Err(PoolManagerError::NotTheLoanOwner)
+            }
+            if loan_info.already_liquidated {
+                return Err(PoolManagerError::LoanAlreadyLiquidated)
+            }
+            // open-ended loans can't move to the rates of fixed terms
+            if loan_info.maturity.is_none() {
+                return Err(PoolManagerError::OpenEndedLoan)
+            }
+            // rolling over borrows the asset for another term
+            self.check_market_active(loan_info.borrow_token)?;
+            self.check_market_not_paused(loan_info.collateral_token)?;
+            check_operation_not_paused(self, BORROW, &Some(loan_info.borrow_token))?;
+            let interest_rate = self.get_term_rate(term).ok_or(PoolManagerError::TermNotSupported)?;
+            // an expired loan can only be repaid or liquidated
+            if self.is_loan_expired(&loan_info) {
+                return Err(PoolManagerError::LoanExpired)
+            }
+            // the owner pays the interest accrued until now
+            let interest = self.calculate_debt(&loan_info) - loan_info.borrow_amount;
+            if PSP22Ref::allowance(&loan_info.borrow_token, owner, contract) < interest {
+                return Err(PoolManagerError::InsufficientAllowance)
+            }
+            if PSP22Ref::balance_of(&loan_info.borrow_token, owner) < interest {
+                return Err(PoolManagerError::InsufficientBalance)
+            }
+            PSP22Ref::transfer_from_builder(&loan_info.borrow_token, owner, contract, interest, Vec::<u8>::new())
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            let now = self.env().block_timestamp();
+            LoanRef::set_loan_info(
+                &loan_account,
+                loan_id,
+                LoanInfo {
+                    timestamp: now,
+                    maturity: Some(now + term),
+                    interest_rate: interest_rate,
+                    ..loan_info
+                },
+            )?;
+            // paid interest fills waiting withdrawal requests
+            self.fill_withdrawal_queue(loan_info.borrow_token);
+            Ok(interest)
         }
 
         /// # Returns
-        /// Returns a Balance
+        /// Returns a Vec of loan Ids
         ///
         /// # Arguments
         /// * `&mut self` - used so we can mutate variables of self component
-        /// * `asset_from` - AccountId of source asset from which the conversion takes place
-        /// * `asset_to` - AccountId of destionation asset to which the conversion takes place
-        /// * `amount_from` - Balance of how much of asset_from we want to transfer to asset_to
+        /// * `offset` - number of loan Ids to skip
+        /// * `limit` - maximum number of loan Ids to scan
+        /// * `within` - time from now in which the maturity of the loan has to be
         ///
         /// # Description
-        /// get_conversion_rate is helper function responsible for getting conversion rates between assets
-        /// It says us how many of asset_to assets do we get for amount_from of asset_from asset.
+        /// get_loans_approaching_maturity is helper function scanning the loan list of the Loan contract
+        /// and returning fixed-term loans which mature until now plus within, including already matured ones
         #[ink(message)]
-        fn get_conversion_rate(&mut self, asset_from: AccountId, asset_to: AccountId, amount_from: Balance) -> Balance {
-            // get conversion rate from mapping
-            let price = self.conversion_rates.get((&asset_from, &asset_to)).unwrap_or(0) * amount_from;
-            price
+        fn get_loans_approaching_maturity(&mut self, offset: u32, limit: u32, within: Timestamp) -> Vec<Id> {
+            let deadline = self.env().block_timestamp() + within;
+            let mut loans = Vec::new();
+            for (loan_id, loan_info) in LoanRef::get_loan_list(&(self.loan_account), offset, limit) {
+                if loan_info.already_liquidated {
+                    continue
+                }
+                if let Some(maturity) = loan_info.maturity {
+                    if maturity <= deadline {
+                        loans.push(loan_id);
+                    }
+                }
+            }
+            loans
         }
 
         /// # Returns
-        /// Returns an AccountId with success and PoolManagerError otherwise
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
         ///
         /// # Arguments
         /// * `&mut self` - used so we can mutate variables of self component
-        /// * `btoken_address` - AccountId of bToken from which we want to get its bounded asset address
+        /// * `term` - time until maturity of the fixed-term loan
+        /// * `interest_rate` - optional APY of the term in basis points, None stops offering the term
         ///
         /// # Description
-        /// get_asset_from_btoken is helper function
-        /// It is responsible for getting asset address which is bound to btoken address.
+        /// set_term_rate is RISK_ADMIN function to offer terms of fixed-term loans, usually discounted under APY.
+        /// Existing loans keep their rate until they are rolled over. Bounds of TermRate apply to offered rates.
         #[ink(message)]
-        fn get_asset_from_btoken(&mut self, btoken_address: AccountId) -> Result<AccountId, PoolManagerError>{
-            // get asset address from pool_token_address
-            let asset_address = self.btoken_asset.get(&btoken_address).unwrap_or(ZERO_ADDRESS.into());
-            // check if we got something
-            if asset_address.is_zero() {
-                return Err(PoolManagerError::AssetNotFound)
+        #[modifiers(only_role(RISK_ADMIN))]
+        fn set_term_rate(&mut self, term: Timestamp, interest_rate: Option<Balance>) -> Result<(), PoolManagerError> {
+            match interest_rate {
+                Some(interest_rate) => {
+                    if term == 0 || interest_rate > BASIS_POINTS {
+                        return Err(PoolManagerError::InvalidInterestRate)
+                    }
+                    // check the rate of the term against its bounds
+                    let previous_rate = self.get_term_rate(term);
+                    self.check_parameter_change(ParameterKind::TermRate, &term, previous_rate, Some(interest_rate))?;
+                    self.term_rates.insert(&term, &interest_rate);
+                }
+                None => self.term_rates.remove(&term),
             }
-            Ok(asset_address)
+            Ok(())
         }
 
         /// # Returns
-        /// Returns an AccountId with success and PoolManagerError otherwise
+        /// Returns an optional Balance in basis points, None if the term is not offered
         ///
         /// # Arguments
         /// * `&mut self` - used so we can mutate variables of self component
-        /// * `asset_address` - AccountId of asset from which we want to get its btoken address
+        /// * `term` - time until maturity of the fixed-term loan
         ///
         /// # Description
-        /// get_btoken_from_asset is helper function
-        /// It is responsible for getting address of btoken for supplying asset addres into the contract
+        /// get_term_rate is helper function returning the APY of the term
         #[ink(message)]
-        fn get_btoken_from_asset(&mut self, asset_address: AccountId) -> Result<AccountId, PoolManagerError> {
-            // get btoken address from asset address
-            let btoken_address = self.asset_btoken.get(&asset_address).unwrap_or(ZERO_ADDRESS.into());
-            // check if we got something
-            if btoken_address.is_zero() {
-                return Err(PoolManagerError::AssetNotFound)
+        fn get_term_rate(&mut self, term: Timestamp) -> Option<Balance> {
+            self.term_rates.get(&term)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `grace_period` - time after maturity when the loan becomes liquidatable
+        ///
+        /// # Description
+        /// set_grace_period is RISK_ADMIN function to change the grace period of fixed-term loans,
+        /// it can't exceed MAX_GRACE_PERIOD and it is checked against bounds of GracePeriod
+        #[ink(message)]
+        #[modifiers(only_role(RISK_ADMIN))]
+        fn set_grace_period(&mut self, grace_period: Timestamp) -> Result<(), PoolManagerError> {
+            if grace_period > MAX_GRACE_PERIOD {
+                return Err(PoolManagerError::InvalidGracePeriod)
             }
-            Ok(btoken_address)
+            let previous = self.get_grace_period();
+            self.check_parameter_change(ParameterKind::GracePeriod, &(), Some(previous as Balance), Some(grace_period as Balance))?;
+            self.grace_period = Some(grace_period);
+            Ok(())
         }
-    }
 
-    /// This contract will contain constructor and helper functions for trait defined functions.
-    impl PoolManagerContract {
         /// # Returns
-        /// Returns Self
+        /// Returns a Timestamp
         ///
         /// # Arguments
-        /// * `loan_hash` - hash of the loan deployed smart contract
-        /// * `btoken_hash` - hash of the btoken deployed smart contract
+        /// * `&mut self` - used so we can mutate variables of self component
         ///
         /// # Description
-        /// Constructor for initializing our contract.
-        #[ink(constructor, payable)]
-        pub fn new(loan_hash: Hash, btoken_hash: Hash) -> Self{
-            // Use initialize_contract from ink_lang::codegen library.
-            ink_lang::codegen::initialize_contract(|instance: &mut PoolManagerContract| {
-                // to variable caller set AccountId that initialized the contract
-                let caller = instance.env().caller();
-                // function from AccessControl trait, caller is added to admin list
-                instance._init_with_admin(caller);
-                // get hash of btoken contract to local struct
-                instance.btoken_contract_code_hash = btoken_hash;
-                // instantiate loan contract
-                let loan = LoanContractRef::new()
-                    .endowment(0)
-                    .code_hash(loan_hash)
-                    .salt_bytes(&[0xDE, 0xAD, 0xBE, 0xEF])
-                    .instantiate()
-                    .unwrap();
-                instance.loan_account = loan.to_account_id();
-            })
+        /// get_grace_period is helper function returning the grace period of fixed-term loans
+        #[ink(message)]
+        fn get_grace_period(&mut self) -> Timestamp {
+            self.grace_period.unwrap_or(GRACE_PERIOD)
         }
 
         /// # Returns
-        /// Returns an AccountId
+        /// Returns a Balance in basis points with success and PoolManagerError otherwise
         ///
         /// # Arguments
         /// * `&mut self` - used so we can mutate variables of self component
-        /// * `contract_name` optional String for specifying name in Metadata
-        /// * `contract_symbol` optional String for specifying symbol in Metadata
+        /// * `loan_id` - Id of the loan
         ///
         /// # Description
-        /// instantiate_btoken_contract is helper function that creates instance of btoken with given data
-        fn instantiate_btoken_contract(&mut self, contract_name: &str, contract_symbol: &str) -> AccountId {
-            let code_hash = self.btoken_contract_code_hash;
-            let (hash, _) = ink_env::random::<ink_env::DefaultEnvironment>(contract_name.as_bytes()).expect("Failed to get salt");
-            let hash = hash.as_ref();
-            let contract = BTokenContractRef::new(Some(String::from(contract_name)), Some(String::from(contract_symbol)))
+        /// get_loan_health is helper function returning the health of the loan,
+        /// the loan can be liquidated when it is below BASIS_POINTS
+        #[ink(message)]
+        fn get_loan_health(&mut self, loan_id: Id) -> Result<Balance, PoolManagerError> {
+            let loan_info = LoanRef::get_loan_info(&(self.loan_account), loan_id)?;
+            let category = self.get_risk_parameters(loan_info.category);
+            Ok(self.calculate_health(&loan_info, &category))
+        }
+
+        /// # Returns
+        /// Returns a Balance in basis points of the debt with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `loan_id` - Id of the loan
+        ///
+        /// # Description
+        /// get_liquidation_bonus is helper function returning the bonus the liquidator would get now
+        #[ink(message)]
+        fn get_liquidation_bonus(&mut self, loan_id: Id) -> Result<Balance, PoolManagerError> {
+            let loan_info = LoanRef::get_loan_info(&(self.loan_account), loan_id)?;
+            let category = self.get_risk_parameters(loan_info.category);
+            let health = self.calculate_health(&loan_info, &category);
+            Ok(self.calculate_liquidation_bonus(loan_info.collateral_token, health, &category))
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `collateral_address` - AccountId of the collateral
+        /// * `curve` - optional LiquidationBonusCurve, None sets the flat bonus of the loan category
+        ///
+        /// # Description
+        /// set_liquidation_bonus_curve is RISK_ADMIN function to change the liquidation bonus of the collateral.
+        /// The bonus is min_bonus at the liquidation threshold and rises linearly to max_bonus at max_bonus_health,
+        /// all values are in basis points of the debt and max_bonus can't exceed MAX_LIQUIDATION_BONUS. Bounds of MaxLiquidationBonus apply to max_bonus,
+        /// removing the curve falls back to the category bonus bounded by LiquidationBonus.
+        #[ink(message)]
+        #[modifiers(only_role(RISK_ADMIN))]
+        fn set_liquidation_bonus_curve(&mut self, collateral_address: AccountId, curve: Option<LiquidationBonusCurve>) -> Result<(), PoolManagerError> {
+            match curve {
+                Some(curve) => {
+                    if curve.min_bonus > curve.max_bonus || curve.max_bonus > MAX_LIQUIDATION_BONUS || curve.max_bonus_health >= BASIS_POINTS {
+                        return Err(PoolManagerError::InvalidBonusCurve)
+                    }
+                    // check the maximal bonus against its bounds
+                    let previous_bonus = self.get_liquidation_bonus_curve(collateral_address).map(|c| c.max_bonus);
+                    self.check_parameter_change(ParameterKind::MaxLiquidationBonus, &collateral_address, previous_bonus, Some(curve.max_bonus))?;
+                    self.liquidation_bonus_curves.insert(&collateral_address, &curve);
+                }
+                None => self.liquidation_bonus_curves.remove(&collateral_address),
+            }
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns an optional LiquidationBonusCurve, None means the flat bonus of the loan category
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `collateral_address` - AccountId of the collateral
+        ///
+        /// # Description
+        /// get_liquidation_bonus_curve is helper function returning the liquidation bonus curve of the collateral
+        #[ink(message)]
+        fn get_liquidation_bonus_curve(&mut self, collateral_address: AccountId) -> Option<LiquidationBonusCurve> {
+            self.liquidation_bonus_curves.get(&collateral_address)
+        }
+
+        /// # Returns
+        /// Returns a Balance of bought collateral with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `loan_id` - Id of the liquidated loan
+        /// * `collateral_amount` - maximum Balance of collateral the bidder wants to buy
+        ///
+        /// # Description
+        /// bid buys collateral in the auction at its current price, which is paid in the borrowed asset.
+        /// The bidder never pays more than the remaining debt, the proceeds repay the debt of the loan
+        /// and the collateral left after the whole debt is repaid goes back to the owner of the loan.
+        #[ink(message)]
+        fn bid(&mut self, loan_id: Id, collateral_amount: Balance) -> Result<Balance, PoolManagerError> {
+            let bidder = self.env().caller();
+            self.buy_collateral(bidder, loan_id, collateral_amount)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `loan_id` - Id of the liquidated loan
+        ///
+        /// # Description
+        /// close_auction ends the auction which reached its floor price or any auction after shutdown, anyone can call it.
+        /// Unsold collateral is seized by the market and the debt it does not cover at the floor price is bad debt.
+        /// The seized collateral is sold through buy_pool_collateral.
+        #[ink(message)]
+        fn close_auction(&mut self, loan_id: Id) -> Result<(), PoolManagerError> {
+            let auction = self.auctions.get(&loan_id).ok_or(PoolManagerError::AuctionNotFound)?;
+            if !self.is_shutdown() && self.env().block_timestamp() < auction.start_time + auction.config.duration {
+                return Err(PoolManagerError::AuctionNotFinished)
+            }
+            self.finish_auction(loan_id, auction)
+        }
+
+        /// # Returns
+        /// Returns a Balance of paid borrowed asset with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the market which seized the collateral
+        /// * `collateral_address` - AccountId of the seized collateral
+        /// * `collateral_amount` - maximum Balance of collateral the buyer wants to buy
+        ///
+        /// # Description
+        /// buy_pool_collateral sells collateral seized by the market at the auction floor price of the oracle price, anyone can call it.
+        /// The proceeds return to the pool, so the value written down when the collateral was seized goes back to lenders.
+        /// After shutdown the seized collateral is redeemed through redeem_settlement instead.
+        #[ink(message)]
+        fn buy_pool_collateral(&mut self, asset_address: AccountId, collateral_address: AccountId, collateral_amount: Balance) -> Result<Balance, PoolManagerError> {
+            // save the address of the caller = address of the buyer
+            let buyer = self.env().caller();
+            // get the address of the contract
+            let contract = self.env().account_id();
+            self.check_not_shutdown()?;
+            // check if the markets are not paused
+            self.check_market_not_paused(asset_address)?;
+            self.check_market_not_paused(collateral_address)?;
+            check_operation_not_paused(self, LIQUIDATION, &Some(asset_address))?;
+            // calculate the price of the collateral
+            let pool_collateral = self.get_pool_collateral(asset_address, collateral_address);
+            let amount = core::cmp::min(collateral_amount, pool_collateral);
+            let floor = self.get_auction_config(collateral_address).floor;
+            let value = self.get_conversion_rate(collateral_address, asset_address, amount);
+            let cost = (value * floor + BASIS_POINTS - 1) / BASIS_POINTS;
+            if amount == 0 || cost == 0 {
+                return Err(PoolManagerError::AmountNotSupported)
+            }
+            // check if the allowance and balance of the buyer are sufficient
+            if PSP22Ref::allowance(&asset_address, buyer, contract) < cost {
+                return Err(PoolManagerError::InsufficientAllowance)
+            }
+            if PSP22Ref::balance_of(&asset_address, buyer) < cost {
+                return Err(PoolManagerError::InsufficientBalance)
+            }
+            self.remove_pool_collateral(asset_address, collateral_address, amount)?;
+            // exchange the payment for the collateral
+            PSP22Ref::transfer_from_builder(&asset_address, buyer, contract, cost, Vec::<u8>::new())
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            PSP22Ref::transfer_builder(&collateral_address, buyer, amount, Vec::<u8>::new())
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            self.env().emit_event(PoolCollateralSold { asset: asset_address, buyer, collateral: collateral_address, collateral_amount: amount, paid: cost });
+            // proceeds fill waiting withdrawal requests
+            self.fill_withdrawal_queue(asset_address);
+            Ok(cost)
+        }
+
+        /// # Returns
+        /// Returns an optional Auction, None if the collateral of the loan is not auctioned
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `loan_id` - Id of the liquidated loan
+        ///
+        /// # Description
+        /// get_auction is helper function returning the state of the auction
+        #[ink(message)]
+        fn get_auction(&mut self, loan_id: Id) -> Option<Auction> {
+            self.auctions.get(&loan_id)
+        }
+
+        /// # Returns
+        /// Returns a Balance with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `loan_id` - Id of the liquidated loan
+        /// * `collateral_amount` - Balance of the collateral
+        ///
+        /// # Description
+        /// get_auction_price is helper function returning how much of the borrowed asset the collateral costs now
+        #[ink(message)]
+        fn get_auction_price(&mut self, loan_id: Id, collateral_amount: Balance) -> Result<Balance, PoolManagerError> {
+            let auction = self.auctions.get(&loan_id).ok_or(PoolManagerError::AuctionNotFound)?;
+            let factor = self.get_auction_factor(&auction);
+            Ok(self.calculate_auction_price(&auction, collateral_amount, factor))
+        }
+
+        /// # Returns
+        /// Returns a list of loan Ids
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        ///
+        /// # Description
+        /// get_active_auctions is helper function returning loans whose collateral is being auctioned
+        #[ink(message)]
+        fn get_active_auctions(&mut self) -> Vec<Id> {
+            self.active_auctions.clone()
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `collateral_address` - AccountId of the collateral
+        /// * `config` - optional AuctionConfig, None sets default parameters
+        ///
+        /// # Description
+        /// set_auction_config is RISK_ADMIN function to change parameters of future auctions of the collateral.
+        /// Prices are in basis points of the oracle price, the floor can't be above the start premium.
+        /// Every parameter is checked against its bounds, default parameters included.
+        #[ink(message)]
+        #[modifiers(only_role(RISK_ADMIN))]
+        fn set_auction_config(&mut self, collateral_address: AccountId, config: Option<AuctionConfig>) -> Result<(), PoolManagerError> {
+            // check every parameter of the config that will apply against its bounds
+            let previous = self.get_auction_config(collateral_address);
+            let new = config.unwrap_or(AuctionConfig {
+                start_premium: AUCTION_START_PREMIUM,
+                floor: AUCTION_FLOOR,
+                duration: AUCTION_DURATION,
+            });
+            self.check_parameter_change(ParameterKind::AuctionStartPremium, &collateral_address, Some(previous.start_premium), Some(new.start_premium))?;
+            self.check_parameter_change(ParameterKind::AuctionFloor, &collateral_address, Some(previous.floor), Some(new.floor))?;
+            self.check_parameter_change(ParameterKind::AuctionDuration, &collateral_address, Some(previous.duration as Balance), Some(new.duration as Balance))?;
+            match config {
+                Some(config) => {
+                    if config.floor == 0 || config.floor > config.start_premium || config.duration == 0 {
+                        return Err(PoolManagerError::InvalidAuctionConfig)
+                    }
+                    self.auction_configs.insert(&collateral_address, &config);
+                }
+                None => self.auction_configs.remove(&collateral_address),
+            }
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns an AuctionConfig
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `collateral_address` - AccountId of the collateral
+        ///
+        /// # Description
+        /// get_auction_config is helper function returning parameters of auctions of the collateral
+        #[ink(message)]
+        fn get_auction_config(&mut self, collateral_address: AccountId) -> AuctionConfig {
+            self.auction_configs.get(&collateral_address).unwrap_or(AuctionConfig {
+                start_premium: AUCTION_START_PREMIUM,
+                floor: AUCTION_FLOOR,
+                duration: AUCTION_DURATION,
+            })
+        }
+
+        /// # Returns
+        /// Returns a bool
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset we want to know if it is lendable to the contract.
+        ///
+        /// # Description
+        /// get_asset_acceptance is helper function
+        /// It is responsible for getting bool representation of acceptance of the specific asset.
+        #[ink(message)]
+        fn get_asset_acceptance(&mut self, asset_address: AccountId) -> bool {
+            let acceptance = !self.asset_btoken.get(&asset_address).unwrap_or(ZERO_ADDRESS.into()).is_zero();
+            acceptance
+        }
+
+        /// # Returns
+        /// Returns a bool
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset we want to know if it is collateralizable
+        ///
+        /// # Description
+        /// get_collateral_acceptance is helper function
+        /// It is responsible for getting bool representation of acceptance of the specific collateral asset.
+        #[ink(message)]
+        fn get_collateral_acceptance(&mut self, asset_address: AccountId) -> bool {
+            self.collateral_accepted.get(&asset_address).unwrap_or(false)
+        }
+
+        /// # Returns
+        /// Returns a Balance with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset from which we want to get its total Balance
+        ///
+        /// # Description
+        /// get_total_asset is helper function
+        /// It is responsible for getting total asset Balance for given asset addresss (lended + in pool).
+        #[ink(message)]
+        fn get_total_asset(&mut self, asset_address: AccountId) -> Result<Balance, PoolManagerError> {
+            // get asset lended from asset address
+            let asset_lended = self.assets_lended.get(&asset_address).unwrap_or(ZERO_ADDRESS.into());
+            // check if we got something
+            if asset_lended.is_zero() {
+                return Err(PoolManagerError::AssetNotFound)
+            }
+            // get AccountId of executed contract
+            let contract = self.env().account_id();
+            // calculate assets in contract without protocol reserves
+            let available = PSP22Ref::balance_of(&asset_address, contract) - self.get_protocol_reserves(asset_address);
+            // calculate assets lended
+            let unavailable = PSP22Ref::balance_of(&asset_lended, contract);
+            Ok(available + unavailable)
+        }
+
+        /// # Returns
+        /// Returns a Balance with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset from which we want to get btoken bounded total Balance
+        ///
+        /// # Description
+        /// get_total_btoken is helper function
+        /// It is responsible for getting total Balance for given pool addresss.
+        #[ink(message)]
+        fn get_total_btoken(&mut self, asset_address: AccountId) -> Result<Balance, PoolManagerError>{
+            let btoken_address = self.get_btoken_from_asset(asset_address)?;
+            let total = PSP22Ref::total_supply(&btoken_address);
+            Ok(total)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset to be allowed
+        /// * `name` optional String for specifying our shares name in Metadata
+        /// * `symbol` optional String for specifying our shares symbol in Metadata
+        /// * `decimal` u8 for specifying our shares decimals in Metadata
+        ///
+        /// # Description
+        /// set_asset_allowance is LISTING_ADMIN function to make asset lendable and borrowable
+        #[ink(message, payable)]
+        #[modifiers(only_role(LISTING_ADMIN))]
+        fn set_asset_allowance(&mut self, asset_address: AccountId) -> Result<(), PoolManagerError> {
+            // check for asset allowance
+            if self.get_asset_acceptance(asset_address) {
+                return Err(PoolManagerError::AssetAlreadySupported)
+            }
+            // instantiate the btoken for lended assets and pool token for reserves
+            let btoken_address = self.instantiate_btoken_contract("BTokenShares", "BTKS");
+            let reserves_address = self.instantiate_btoken_contract("TokenReserves", "TRS");
+            // accept the asset by inserting it into the mappings
+            self.assets_lended.insert(&asset_address, &reserves_address);
+            self.asset_btoken.insert(&asset_address, &btoken_address);
+            self.btoken_asset.insert(&btoken_address, &asset_address);
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset to be allowed as a collateral
+        ///
+        /// # Description
+        /// set_collateral_allowance is LISTING_ADMIN function to make asset collateralizable for the loans
+        #[ink(message)]
+        #[modifiers(only_role(LISTING_ADMIN))]
+        fn set_collateral_allowance(&mut self, asset_address: AccountId) -> Result<(), PoolManagerError> {
+            // check for collateral allowance
+            if self.get_collateral_acceptance(asset_address) {
+                return Err(PoolManagerError::CollateralAlreadySupported)
+            }
+            // accept the collateral by inserting it into the mapping
+            self.collateral_accepted.insert(&asset_address, &true);
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset to be disallowed
+        ///
+        /// # Description
+        /// set_asset_disallowance is LISTING_ADMIN function to make asset unlendable
+        #[ink(message)]
+        #[modifiers(only_role(LISTING_ADMIN))]
+        fn set_asset_disallowance(&mut self, asset_address: AccountId) -> Result<(), PoolManagerError> {
+            // obtain btoken address
+            let btoken_address = self.get_btoken_from_asset(asset_address)?;
+            // get btoken status and check for pool emptiness
+            let asset_balance = PSP22Ref::balance_of(&asset_address, self.env().account_id());
+            let btoken_balance = PSP22Ref::balance_of(&btoken_address, self.env().account_id());
+            if asset_balance > 0 || btoken_balance > 0{
+                return Err(PoolManagerError::PoolIsNotEmpty)
+            }
+            self.assets_lended.remove(&asset_address);
+            self.asset_btoken.remove(&asset_address);
+            self.btoken_asset.remove(&btoken_address);
+            self.supply_caps.remove(&asset_address);
+            self.borrow_caps.remove(&asset_address);
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset to be disallowed as a collateral
+        ///
+        /// # Description
+        /// set_collateral_disallowance is LISTING_ADMIN function to make asset uncollateralizable
+        #[ink(message)]
+        #[modifiers(only_role(LISTING_ADMIN))]
+        fn set_collateral_disallowance(&mut self, asset_address: AccountId) -> Result<(), PoolManagerError> {
+            // check for collateral disallowance
+            if !self.get_collateral_acceptance(asset_address) {
+                return Err(PoolManagerError::CollateralAlreadyUnsupported)
+            }
+            // disallow the collateral by setting its value to false
+            self.collateral_accepted.insert(&asset_address, &false);
+            Ok(())
+        }
+
+        /// # Returns
+        /// Without a return value
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_from` - AccountId of source asset from which the conversion takes place
+        /// * `asset_to` - AccountId of destionation asset to which the conversion takes place
+        /// * `exchange_rate` - Balance of 1 asset_from to X asset_to conversion
+        ///
+        /// # Description
+        /// set_conversion_rate function is helper function responsible for setting conversion rates between assets
+        /// It says us how many of asset_to assets do we get for 1 asset_from asset.
+        #[ink(message)]
+        #[modifiers(only_role(ORACLE_UPDATER))]
+        fn set_conversion_rate(&mut self, asset_from: AccountId, asset_to: AccountId, exchange_rate: Balance) -> Result<(), PoolManagerError> {
+            // prices are frozen after shutdown
+            self.check_not_shutdown()?;
+            // check the rate against its bounds
+            let previous_rate = self.conversion_rates.get((&asset_from, &asset_to));
+            self.check_parameter_change(ParameterKind::ConversionRate, &(asset_from, asset_to), previous_rate, Some(exchange_rate))?;
+            self.conversion_rates.insert((&asset_from, &asset_to), &exchange_rate);
+            // pause markets of both assets if the rate moved too much
+            self.check_price_deviation(asset_from, asset_from, asset_to, previous_rate, exchange_rate);
+            self.check_price_deviation(asset_to, asset_from, asset_to, previous_rate, exchange_rate);
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_from` - AccountId of source asset from which the conversion takes place
+        /// * `asset_to` - AccountId of destionation asset to which the conversion takes place
+        /// * `amount_from` - Balance of how much of asset_from we want to transfer to asset_to
+        ///
+        /// # Description
+        /// get_conversion_rate is helper function responsible for getting conversion rates between assets
+        /// It says us how many of asset_to assets do we get for amount_from of asset_from asset.
+        #[ink(message)]
+        fn get_conversion_rate(&mut self, asset_from: AccountId, asset_to: AccountId, amount_from: Balance) -> Balance {
+            // get conversion rate from mapping
+            let price = self.conversion_rates.get((&asset_from, &asset_to)).unwrap_or(0) * amount_from;
+            price
+        }
+
+        /// # Returns
+        /// Returns an AccountId with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `btoken_address` - AccountId of bToken from which we want to get its bounded asset address
+        ///
+        /// # Description
+        /// get_asset_from_btoken is helper function
+        /// It is responsible for getting asset address which is bound to btoken address.
+        #[ink(message)]
+        fn get_asset_from_btoken(&mut self, btoken_address: AccountId) -> Result<AccountId, PoolManagerError>{
+            // get asset address from pool_token_address
+            let asset_address = self.btoken_asset.get(&btoken_address).unwrap_or(ZERO_ADDRESS.into());
+            // check if we got something
+            if asset_address.is_zero() {
+                return Err(PoolManagerError::AssetNotFound)
+            }
+            Ok(asset_address)
+        }
+
+        /// # Returns
+        /// Returns an AccountId with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset from which we want to get its btoken address
+        ///
+        /// # Description
+        /// get_btoken_from_asset is helper function
+        /// It is responsible for getting address of btoken for supplying asset addres into the contract
+        #[ink(message)]
+        fn get_btoken_from_asset(&mut self, asset_address: AccountId) -> Result<AccountId, PoolManagerError> {
+            // get btoken address from asset address
+            let btoken_address = self.asset_btoken.get(&asset_address).unwrap_or(ZERO_ADDRESS.into());
+            // check if we got something
+            if btoken_address.is_zero() {
+                return Err(PoolManagerError::AssetNotFound)
+            }
+            Ok(btoken_address)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset whose supply cap is set
+        /// * `supply_cap` - maximum total Balance of the pool, None removes the cap
+        ///
+        /// # Description
+        /// set_supply_cap is RISK_ADMIN function to limit how much of the asset can be lended into its pool
+        #[ink(message)]
+        #[modifiers(only_role(RISK_ADMIN))]
+        fn set_supply_cap(&mut self, asset_address: AccountId, supply_cap: Option<Balance>) -> Result<(), PoolManagerError> {
+            // check if the asset is supported
+            if !self.get_asset_acceptance(asset_address) {
+                return Err(PoolManagerError::AssetNotSupported)
+            }
+            // check the cap against its bounds
+            let previous_cap = self.supply_caps.get(&asset_address);
+            self.check_parameter_change(ParameterKind::SupplyCap, &asset_address, previous_cap, supply_cap)?;
+            match supply_cap {
+                Some(cap) => self.supply_caps.insert(&asset_address, &cap),
+                None => self.supply_caps.remove(&asset_address),
+            }
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset whose borrow cap is set
+        /// * `borrow_cap` - maximum total Balance borrowed from the pool, None removes the cap
+        ///
+        /// # Description
+        /// set_borrow_cap is RISK_ADMIN function to limit how much of the asset can be borrowed from its pool
+        #[ink(message)]
+        #[modifiers(only_role(RISK_ADMIN))]
+        fn set_borrow_cap(&mut self, asset_address: AccountId, borrow_cap: Option<Balance>) -> Result<(), PoolManagerError> {
+            // check if the asset is supported
+            if !self.get_asset_acceptance(asset_address) {
+                return Err(PoolManagerError::AssetNotSupported)
+            }
+            // check the cap against its bounds
+            let previous_cap = self.borrow_caps.get(&asset_address);
+            self.check_parameter_change(ParameterKind::BorrowCap, &asset_address, previous_cap, borrow_cap)?;
+            match borrow_cap {
+                Some(cap) => self.borrow_caps.insert(&asset_address, &cap),
+                None => self.borrow_caps.remove(&asset_address),
+            }
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns an optional Balance, None means the supply is unlimited
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset we want to know the supply cap of
+        ///
+        /// # Description
+        /// get_supply_cap is helper function returning the supply cap of the asset pool
+        #[ink(message)]
+        fn get_supply_cap(&mut self, asset_address: AccountId) -> Option<Balance> {
+            self.supply_caps.get(&asset_address)
+        }
+
+        /// # Returns
+        /// Returns an optional Balance, None means the borrowing is unlimited
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset we want to know the borrow cap of
+        ///
+        /// # Description
+        /// get_borrow_cap is helper function returning the borrow cap of the asset pool
+        #[ink(message)]
+        fn get_borrow_cap(&mut self, asset_address: AccountId) -> Option<Balance> {
+            self.borrow_caps.get(&asset_address)
+        }
+
+        /// # Returns
+        /// Returns an optional Balance with success and PoolManagerError otherwise
+        /// None means the supply is unlimited
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset we want to know the remaining supply capacity of
+        ///
+        /// # Description
+        /// get_remaining_supply_capacity is helper function
+        /// It is responsible for getting how much of the asset can still be lended into its pool.
+        #[ink(message)]
+        fn get_remaining_supply_capacity(&mut self, asset_address: AccountId) -> Result<Option<Balance>, PoolManagerError> {
+            let total_asset = self.get_total_asset(asset_address)?;
+            Ok(self.supply_caps.get(&asset_address).map(|cap| cap.saturating_sub(total_asset)))
+        }
+
+        /// # Returns
+        /// Returns an optional Balance with success and PoolManagerError otherwise
+        /// None means the borrowing is unlimited
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset we want to know the remaining borrow capacity of
+        ///
+        /// # Description
+        /// get_remaining_borrow_capacity is helper function
+        /// It is responsible for getting how much of the asset can still be borrowed from its pool.
+        #[ink(message)]
+        fn get_remaining_borrow_capacity(&mut self, asset_address: AccountId) -> Result<Option<Balance>, PoolManagerError> {
+            let total_borrowed = self.get_total_borrowed(asset_address)?;
+            Ok(self.borrow_caps.get(&asset_address).map(|cap| cap.saturating_sub(total_borrowed)))
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `collateral_address` - AccountId of collateral to be isolated
+        /// * `isolated` - bool whether the collateral is isolated
+        ///
+        /// # Description
+        /// set_collateral_isolation is RISK_ADMIN function to list risky collateral as isolated.
+        /// Isolated collateral can only back assets allowed by set_isolated_borrowable up to their debt ceilings.
+        #[ink(message)]
+        #[modifiers(only_role(RISK_ADMIN))]
+        fn set_collateral_isolation(&mut self, collateral_address: AccountId, isolated: bool) -> Result<(), PoolManagerError> {
+            // check if the collateral is accepted
+            if !self.get_collateral_acceptance(collateral_address) {
+                return Err(PoolManagerError::CollateralNotSupported)
+            }
+            self.isolated_collaterals.insert(&collateral_address, &isolated);
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `collateral_address` - AccountId of the isolated collateral
+        /// * `asset_address` - AccountId of asset to be borrowable against the collateral
+        /// * `debt_ceiling` - maximum total debt in the asset backed by the collateral, None makes the asset not borrowable
+        ///
+        /// # Description
+        /// set_isolated_borrowable is RISK_ADMIN function to configure which assets isolated collateral can back.
+        /// The debt ceiling is kept per borrowed asset, so it is always in units of that asset.
+        #[ink(message)]
+        #[modifiers(only_role(RISK_ADMIN))]
+        fn set_isolated_borrowable(&mut self, collateral_address: AccountId, asset_address: AccountId, debt_ceiling: Option<Balance>) -> Result<(), PoolManagerError> {
+            // check if the asset is supported
+            if !self.get_asset_acceptance(asset_address) {
+                return Err(PoolManagerError::AssetNotSupported)
+            }
+            // check the ceiling against its bounds
+            let previous_ceiling = self.get_debt_ceiling(collateral_address, asset_address);
+            self.check_parameter_change(ParameterKind::DebtCeiling, &(collateral_address, asset_address), previous_ceiling, debt_ceiling)?;
+            match debt_ceiling {
+                Some(ceiling) => self.debt_ceilings.insert((&collateral_address, &asset_address), &ceiling),
+                None => self.debt_ceilings.remove((&collateral_address, &asset_address)),
+            }
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a bool
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `collateral_address` - AccountId of collateral we want to know if it is isolated
+        ///
+        /// # Description
+        /// is_collateral_isolated is helper function
+        /// It is responsible for getting bool representation of isolation of the collateral.
+        #[ink(message)]
+        fn is_collateral_isolated(&mut self, collateral_address: AccountId) -> bool {
+            self.isolated_collaterals.get(&collateral_address).unwrap_or(false)
+        }
+
+        /// # Returns
+        /// Returns an optional Balance, None means the asset is not borrowable against the isolated collateral
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `collateral_address` - AccountId of collateral we want to know the debt ceiling of
+        /// * `asset_address` - AccountId of the borrowed asset the ceiling is in
+        ///
+        /// # Description
+        /// get_debt_ceiling is helper function returning the debt ceiling of the asset backed by isolated collateral
+        #[ink(message)]
+        fn get_debt_ceiling(&mut self, collateral_address: AccountId, asset_address: AccountId) -> Option<Balance> {
+            self.debt_ceilings.get((&collateral_address, &asset_address))
+        }
+
+        /// # Returns
+        /// Returns a bool
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `collateral_address` - AccountId of the isolated collateral
+        /// * `asset_address` - AccountId of asset we want to know if it is borrowable against the collateral
+        ///
+        /// # Description
+        /// get_isolated_borrowable is helper function
+        /// It is responsible for getting bool representation of borrowability of the asset in isolation.
+        #[ink(message)]
+        fn get_isolated_borrowable(&mut self, collateral_address: AccountId, asset_address: AccountId) -> bool {
+            self.get_debt_ceiling(collateral_address, asset_address).is_some()
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `collateral_address` - AccountId of collateral we want to know the backed debt of
+        /// * `asset_address` - AccountId of the borrowed asset
+        ///
+        /// # Description
+        /// get_collateral_debt is helper function
+        /// It is responsible for getting total debt in the asset of all loans backed by the collateral.
+        #[ink(message)]
+        fn get_collateral_debt(&mut self, collateral_address: AccountId, asset_address: AccountId) -> Balance {
+            self.collateral_debt.get((&collateral_address, &asset_address)).unwrap_or(0)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `category_id` - Id of the category to be defined or updated
+        /// * `category` - AssetCategory with risk parameters of the category
+        ///
+        /// # Description
+        /// set_category is RISK_ADMIN function to define an efficiency mode category for correlated assets
+        #[ink(message)]
+        #[modifiers(only_role(RISK_ADMIN))]
+        fn set_category(&mut self, category_id: CategoryId, category: AssetCategory) -> Result<(), PoolManagerError> {
+            // the category must not collide with positions without efficiency mode,
+            // the loan to value must stay below the liquidation threshold and the bonus can not exceed MAX_LIQUIDATION_BONUS
+            if category_id == NO_CATEGORY
+                || category.ltv >= category.liquidation_threshold
+                || category.liquidation_threshold > 100
+                || category.liquidation_bonus * BASIS_POINTS / 100 > MAX_LIQUIDATION_BONUS
+            {
+                return Err(PoolManagerError::InvalidCategory)
+            }
+            // check every risk parameter of the category against its bounds
+            let previous = self.categories.get(&category_id);
+            self.check_parameter_change(ParameterKind::LoanToValue, &category_id, previous.as_ref().map(|c| c.ltv), Some(category.ltv))?;
+            self.check_parameter_change(ParameterKind::LiquidationThreshold, &category_id, previous.as_ref().map(|c| c.liquidation_threshold), Some(category.liquidation_threshold))?;
+            self.check_parameter_change(ParameterKind::LiquidationBonus, &category_id, previous.as_ref().map(|c| c.liquidation_bonus), Some(category.liquidation_bonus))?;
+            self.categories.insert(&category_id, &category);
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset to be added to the category
+        /// * `category_id` - Id of the category, NO_CATEGORY removes the asset from its category
+        ///
+        /// # Description
+        /// set_asset_category is RISK_ADMIN function to assign asset to an efficiency mode category
+        #[ink(message)]
+        #[modifiers(only_role(RISK_ADMIN))]
+        fn set_asset_category(&mut self, asset_address: AccountId, category_id: CategoryId) -> Result<(), PoolManagerError> {
+            if category_id == NO_CATEGORY {
+                self.asset_categories.remove(&asset_address);
+                return Ok(())
+            }
+            // check if the category exists
+            if self.get_category(category_id).is_none() {
+                return Err(PoolManagerError::CategoryNotFound)
+            }
+            self.asset_categories.insert(&asset_address, &category_id);
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `category_id` - Id of the category, NO_CATEGORY leaves efficiency mode
+        ///
+        /// # Description
+        /// set_user_category lets the caller opt new loans into an efficiency mode category.
+        /// Existing loans keep the risk parameters they were created with.
+        #[ink(message)]
+        fn set_user_category(&mut self, category_id: CategoryId) -> Result<(), PoolManagerError> {
+            let user = self.env().caller();
+            if category_id == NO_CATEGORY {
+                self.user_categories.remove(&user);
+                return Ok(())
+            }
+            // check if the category exists
+            if self.get_category(category_id).is_none() {
+                return Err(PoolManagerError::CategoryNotFound)
+            }
+            self.user_categories.insert(&user, &category_id);
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns an optional AssetCategory, None if the category does not exist
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `category_id` - Id of the category we want to get
+        ///
+        /// # Description
+        /// get_category is helper function returning risk parameters of the category
+        #[ink(message)]
+        fn get_category(&mut self, category_id: CategoryId) -> Option<AssetCategory> {
+            self.categories.get(&category_id)
+        }
+
+        /// # Returns
+        /// Returns a CategoryId, NO_CATEGORY if the asset is not in any category
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset we want to know the category of
+        ///
+        /// # Description
+        /// get_asset_category is helper function returning the category of the asset
+        #[ink(message)]
+        fn get_asset_category(&mut self, asset_address: AccountId) -> CategoryId {
+            self.asset_categories.get(&asset_address).unwrap_or(NO_CATEGORY)
+        }
+
+        /// # Returns
+        /// Returns a CategoryId, NO_CATEGORY if the user is not in efficiency mode
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `user` - AccountId of user we want to know the category of
+        ///
+        /// # Description
+        /// get_user_category is helper function returning the category the user opted into
+        #[ink(message)]
+        fn get_user_category(&mut self, user: AccountId) -> CategoryId {
+            self.user_categories.get(&user).unwrap_or(NO_CATEGORY)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset whose market state is changed
+        /// * `state` - new MarketState of the market
+        ///
+        /// # Description
+        /// set_market_state is LISTING_ADMIN function to move a market through its lifecycle.
+        /// Frozen markets accept no new lends or borrows, paused markets stop all actions
+        /// and delisted markets are wound down for good, only letting users exit.
+        #[ink(message)]
+        #[modifiers(only_role(LISTING_ADMIN))]
+        fn set_market_state(&mut self, asset_address: AccountId, state: MarketState) -> Result<(), PoolManagerError> {
+            // delisting can not be reverted
+            if self.get_market_state(asset_address) == MarketState::Delisted {
+                return Err(PoolManagerError::MarketDelisted)
+            }
+            self.market_states.insert(&asset_address, &state);
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a MarketState
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset we want to know the market state of
+        ///
+        /// # Description
+        /// get_market_state is helper function returning the lifecycle state of the asset market
+        #[ink(message)]
+        fn get_market_state(&mut self, asset_address: AccountId) -> MarketState {
+            self.market_states.get(&asset_address).unwrap_or_default()
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        ///
+        /// # Description
+        /// pause is PAUSE_GUARDIAN function to stop every operation of the protocol
+        #[ink(message)]
+        #[modifiers(only_role(PAUSE_GUARDIAN))]
+        fn pause(&mut self) -> Result<(), PoolManagerError> {
+            self._pause()
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        ///
+        /// # Description
+        /// unpause is ADMIN function to resume the protocol after pause
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        fn unpause(&mut self) -> Result<(), PoolManagerError> {
+            self._unpause()
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `operations` - OperationMask of operations to be paused (LEND, BORROW, REPAY, WITHDRAW, LIQUIDATION, FLASHLOAN)
+        /// * `asset_address` - AccountId of asset to pause the operations for, None pauses them for every asset
+        ///
+        /// # Description
+        /// pause_operations is PAUSE_GUARDIAN function to pause chosen operations independently
+        #[ink(message)]
+        #[modifiers(only_role(PAUSE_GUARDIAN))]
+        fn pause_operations(&mut self, operations: OperationMask, asset_address: Option<AccountId>) -> Result<(), PoolManagerError> {
+            self._pause_operations(operations, asset_address)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `operations` - OperationMask of operations to be unpaused
+        /// * `asset_address` - AccountId of asset to unpause the operations for, None unpauses the global pause
+        ///
+        /// # Description
+        /// unpause_operations is ADMIN function to resume operations paused by pause_operations
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        fn unpause_operations(&mut self, operations: OperationMask, asset_address: Option<AccountId>) -> Result<(), PoolManagerError> {
+            self._unpause_operations(operations, asset_address)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `kind` - ParameterKind of the bounded parameter
+        /// * `bounds` - ParameterBounds with absolute min and max, maximum relative change in basis points
+        ///   (0 disables it) and the window the change is measured in, None removes the bounds
+        ///
+        /// # Description
+        /// set_parameter_bounds is ADMIN function to limit values risk and oracle admins can set.
+        /// Every numeric setter is bounded by its ParameterKind, only addresses (swap venues, safety modules) are not.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        fn set_parameter_bounds(&mut self, kind: ParameterKind, bounds: Option<ParameterBounds>) -> Result<(), PoolManagerError> {
+            match bounds {
+                Some(bounds) => {
+                    if bounds.min > bounds.max {
+                        return Err(PoolManagerError::InvalidParameterBounds)
+                    }
+                    self.parameter_bounds.insert(&kind, &bounds);
+                }
+                None => self.parameter_bounds.remove(&kind),
+            }
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns an optional ParameterBounds, None means the parameter is not bounded
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `kind` - ParameterKind of the parameter
+        ///
+        /// # Description
+        /// get_parameter_bounds is helper function returning the bounds of the parameter
+        #[ink(message)]
+        fn get_parameter_bounds(&mut self, kind: ParameterKind) -> Option<ParameterBounds> {
+            self.parameter_bounds.get(&kind)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset protected by the circuit breaker
+        /// * `config` - CircuitBreakerConfig with maximum price deviation in basis points within price window
+        ///   and maximum outflow in one block, 0 disables the check, None removes the circuit breaker
+        ///
+        /// # Description
+        /// set_circuit_breaker is RISK_ADMIN function to configure when the market is paused automatically.
+        /// Disabled checks count as unlimited for the bounds of MaxPriceDeviation and MaxBlockOutflow.
+        #[ink(message)]
+        #[modifiers(only_role(RISK_ADMIN))]
+        fn set_circuit_breaker(&mut self, asset_address: AccountId, config: Option<CircuitBreakerConfig>) -> Result<(), PoolManagerError> {
+            // check the limits against their bounds, 0 disables the check
+            let previous = self.get_circuit_breaker(asset_address);
+            let enabled = |value: Balance| if value > 0 { Some(value) } else { None };
+            self.check_parameter_change(
+                ParameterKind::MaxPriceDeviation,
+                &asset_address,
+                previous.as_ref().and_then(|c| enabled(c.max_price_deviation)),
+                config.as_ref().and_then(|c| enabled(c.max_price_deviation)),
+            )?;
+            self.check_parameter_change(
+                ParameterKind::MaxBlockOutflow,
+                &asset_address,
+                previous.as_ref().and_then(|c| enabled(c.max_block_outflow)),
+                config.as_ref().and_then(|c| enabled(c.max_block_outflow)),
+            )?;
+            match config {
+                Some(config) => self.circuit_breakers.insert(&asset_address, &config),
+                None => self.circuit_breakers.remove(&asset_address),
+            }
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns an optional CircuitBreakerConfig, None means the asset is not protected
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset
+        ///
+        /// # Description
+        /// get_circuit_breaker is helper function returning the circuit breaker configuration of the asset
+        #[ink(message)]
+        fn get_circuit_breaker(&mut self, asset_address: AccountId) -> Option<CircuitBreakerConfig> {
+            self.circuit_breakers.get(&asset_address)
+        }
+
+        /// # Returns
+        /// Returns a bool
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset
+        ///
+        /// # Description
+        /// is_circuit_breaker_tripped is helper function returning whether the market was paused by the circuit breaker
+        #[ink(message)]
+        fn is_circuit_breaker_tripped(&mut self, asset_address: AccountId) -> bool {
+            self.tripped_circuit_breakers.get(&asset_address).is_some()
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset whose market was paused by the circuit breaker
+        ///
+        /// # Description
+        /// clear_circuit_breaker is PAUSE_GUARDIAN function to resume the market after the guardian checked it.
+        /// Only operations paused by the circuit breaker are resumed, pauses of the guardian stay.
+        #[ink(message)]
+        #[modifiers(only_role(PAUSE_GUARDIAN))]
+        fn clear_circuit_breaker(&mut self, asset_address: AccountId) -> Result<(), PoolManagerError> {
+            if !self.is_circuit_breaker_tripped(asset_address) {
+                return Err(PoolManagerError::CircuitBreakerNotTripped)
+            }
+            self.tripped_circuit_breakers.remove(&asset_address);
+            // operations could have been unpaused already by unpause_operations
+            let operations = self.circuit_breaker_operations.get(&asset_address).unwrap_or(0);
+            self.circuit_breaker_operations.remove(&asset_address);
+            if operations & paused_operations(self, &Some(asset_address)) != 0 {
+                let _ = self._unpause_operations::<PoolManagerError>(operations, Some(asset_address));
+            }
+            // start new outflow tracking
+            self.block_outflows.remove(&asset_address);
+            self.env().emit_event(CircuitBreakerCleared {
+                asset: asset_address,
+                guardian: self.env().caller(),
+            });
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset whose outflow is limited
+        /// * `config` - RateLimitConfig with capacity of the bucket and period in which empty bucket refills, None removes the limit
+        ///
+        /// # Description
+        /// set_rate_limit is RISK_ADMIN function to limit how much of the asset can leave the pool by withdraw and borrow over time.
+        /// The bucket starts full after every change of the limit, removed limit counts as unlimited capacity for its bounds.
+        #[ink(message)]
+        #[modifiers(only_role(RISK_ADMIN))]
+        fn set_rate_limit(&mut self, asset_address: AccountId, config: Option<RateLimitConfig>) -> Result<(), PoolManagerError> {
+            // check the limit against its bounds
+            let previous = self.get_rate_limit(asset_address);
+            self.check_parameter_change(ParameterKind::RateLimitCapacity, &asset_address, previous.as_ref().map(|c| c.capacity), config.as_ref().map(|c| c.capacity))?;
+            if let Some(config) = config.as_ref() {
+                self.check_parameter_change(
+                    ParameterKind::RateLimitRefillPeriod,
+                    &asset_address,
+                    previous.as_ref().map(|c| c.refill_period as Balance),
+                    Some(config.refill_period as Balance),
+                )?;
+            }
+            match config {
+                Some(config) => self.rate_limits.insert(&asset_address, &config),
+                None => self.rate_limits.remove(&asset_address),
+            }
+            self.rate_limit_buckets.remove(&asset_address);
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns an optional RateLimitConfig, None means the outflow is unlimited
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset
+        ///
+        /// # Description
+        /// get_rate_limit is helper function returning the outflow rate limit of the asset
+        #[ink(message)]
+        fn get_rate_limit(&mut self, asset_address: AccountId) -> Option<RateLimitConfig> {
+            self.rate_limits.get(&asset_address)
+        }
+
+        /// # Returns
+        /// Returns an optional Balance, None means the outflow is unlimited
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset
+        ///
+        /// # Description
+        /// get_remaining_outflow_capacity is helper function returning how much of the asset can leave the pool now
+        #[ink(message)]
+        fn get_remaining_outflow_capacity(&mut self, asset_address: AccountId) -> Option<Balance> {
+            let config = self.rate_limits.get(&asset_address)?;
+            Some(self.get_available_outflow(asset_address, &config))
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the asset
+        /// * `amount` - Balance of the asset deposited by the caller
+        ///
+        /// # Description
+        /// deposit_protocol_reserves is TREASURY function funding the protocol reserves which cover bad debt of the market
+        #[ink(message)]
+        #[modifiers(only_role(TREASURY))]
+        fn deposit_protocol_reserves(&mut self, asset_address: AccountId, amount: Balance) -> Result<(), PoolManagerError> {
+            // save the address of the caller = address of the treasury
+            let treasury = self.env().caller();
+            // check if the asset is supported
+            self.get_btoken_from_asset(asset_address)?;
+            PSP22Ref::transfer_from_builder(&asset_address, treasury, self.env().account_id(), amount, Vec::<u8>::new())
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            let reserves = self.get_protocol_reserves(asset_address);
+            self.protocol_reserves.insert(&asset_address, &(reserves + amount));
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the asset
+        /// * `amount` - Balance of the asset to be withdrawn
+        /// * `to` - AccountId of the receiver
+        ///
+        /// # Description
+        /// withdraw_protocol_reserves is TREASURY function taking the asset out of the protocol reserves
+        #[ink(message)]
+        #[modifiers(only_role(TREASURY))]
+        fn withdraw_protocol_reserves(&mut self, asset_address: AccountId, amount: Balance, to: AccountId) -> Result<(), PoolManagerError> {
+            let reserves = self.get_protocol_reserves(asset_address);
+            if amount > reserves {
+                return Err(PoolManagerError::InsufficientProtocolReserves)
+            }
+            self.protocol_reserves.insert(&asset_address, &(reserves - amount));
+            PSP22Ref::transfer_builder(&asset_address, to, amount, Vec::<u8>::new())
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the asset
+        ///
+        /// # Description
+        /// get_protocol_reserves is helper function returning the Balance of the asset owned by the protocol
+        #[ink(message)]
+        fn get_protocol_reserves(&mut self, asset_address: AccountId) -> Balance {
+            self.protocol_reserves.get(&asset_address).unwrap_or(0)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the borrowed asset
+        /// * `safety_module` - optional AccountId of the safety module, None removes it
+        ///
+        /// # Description
+        /// set_safety_module is ADMIN function to choose the safety module slashed for bad debt of the market.
+        /// The safety module has to stake the asset or its btoken and the contract has to be its slasher.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        fn set_safety_module(&mut self, asset_address: AccountId, safety_module: Option<AccountId>) -> Result<(), PoolManagerError> {
+            let btoken_address = self.get_btoken_from_asset(asset_address)?;
+            match safety_module {
+                Some(safety_module) => {
+                    let staked_token = SafetyModuleRef::get_staked_token(&safety_module);
+                    if staked_token != asset_address && staked_token != btoken_address {
+                        return Err(PoolManagerError::InvalidSafetyModule)
+                    }
+                    if SafetyModuleRef::get_slasher(&safety_module) != self.env().account_id() {
+                        return Err(PoolManagerError::InvalidSafetyModule)
+                    }
+                    self.safety_modules.insert(&asset_address, &safety_module);
+                }
+                None => self.safety_modules.remove(&asset_address),
+            }
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns an optional AccountId, None if the market has no safety module
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the borrowed asset
+        ///
+        /// # Description
+        /// get_safety_module is helper function returning the safety module insuring the market
+        #[ink(message)]
+        fn get_safety_module(&mut self, asset_address: AccountId) -> Option<AccountId> {
+            self.safety_modules.get(&asset_address)
+        }
+
+        /// # Returns
+        /// Returns a BadDebt
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the borrowed asset
+        ///
+        /// # Description
+        /// get_bad_debt is helper function returning the total shortfall of the market and how it was covered
+        #[ink(message)]
+        fn get_bad_debt(&mut self, asset_address: AccountId) -> BadDebt {
+            self.bad_debts.get(&asset_address).unwrap_or_default()
+        }
+
+        /// # Returns
+        /// Returns a Balance with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `loan_id` - Id of the loan
+        ///
+        /// # Description
+        /// get_loan_shortfall is helper function returning the debt which would not be backed by the collateral
+        /// if the loan was liquidated now
+        #[ink(message)]
+        fn get_loan_shortfall(&mut self, loan_id: Id) -> Result<Balance, PoolManagerError> {
+            let loan_info = LoanRef::get_loan_info(&(self.loan_account), loan_id)?;
+            if loan_info.already_liquidated {
+                return Ok(0)
+            }
+            let category = self.get_risk_parameters(loan_info.category);
+            let health = self.calculate_health(&loan_info, &category);
+            let reward = self.calculate_liquidation_reward(&loan_info, health, &category);
+            Ok(self.calculate_shortfall(&loan_info, reward, &category))
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        ///
+        /// # Description
+        /// shutdown is one-way ADMIN function for a catastrophic event. It freezes conversion rates at their last value,
+        /// stops lends, borrows, withdrawals and liquidations. Borrowers reclaim net collateral through settle_loan
+        /// and once every loan of the market is settled btoken holders redeem their share of the pool through redeem_settlement.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        fn shutdown(&mut self) -> Result<(), PoolManagerError> {
+            self.check_not_shutdown()?;
+            let timestamp = self.env().block_timestamp();
+            self.shutdown_timestamp = Some(timestamp);
+            self.env().emit_event(ShutdownTriggered { admin: self.env().caller(), timestamp });
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a bool
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        ///
+        /// # Description
+        /// is_shutdown is helper function returning if the protocol was shut down
+        #[ink(message)]
+        fn is_shutdown(&mut self) -> bool {
+            self.shutdown_timestamp.is_some()
+        }
+
+        /// # Returns
+        /// Returns an optional Timestamp, None while the protocol is running
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        ///
+        /// # Description
+        /// get_shutdown_timestamp is helper function returning when the protocol was shut down
+        #[ink(message)]
+        fn get_shutdown_timestamp(&mut self) -> Option<Timestamp> {
+            self.shutdown_timestamp
+        }
+
+        /// # Returns
+        /// Returns a Balance of collateral returned to the loan owner with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `loan_id` - Id of the loan to be settled
+        ///
+        /// # Description
+        /// settle_loan closes the loan after shutdown at frozen prices, anyone can call it.
+        /// Collateral worth the debt with interest until shutdown is seized by the market of the borrowed asset
+        /// and the rest is returned to the owner of the loan.
+        #[ink(message)]
+        fn settle_loan(&mut self, loan_id: Id) -> Result<Balance, PoolManagerError> {
+            if !self.is_shutdown() {
+                return Err(PoolManagerError::ProtocolNotShutdown)
+            }
+            let loan_account = self.loan_account;
+            let loan_info = LoanRef::get_loan_info(&loan_account, loan_id.clone())?;
+            if loan_info.already_liquidated {
+                return Err(PoolManagerError::LoanAlreadyLiquidated)
+            }
+            // value the collateral at frozen prices
+            let category = self.get_risk_parameters(loan_info.category);
+            let debt = self.calculate_debt(&loan_info);
+            let value = self.get_collateral_value(loan_info.collateral_token, loan_info.borrow_token, loan_info.collateral_amount, &category);
+            // seize collateral worth the debt, all of it if the loan is undercollateralized
+            let seized = if value > debt {
+                debt * loan_info.collateral_amount / value
+            } else {
+                loan_info.collateral_amount
+            };
+            let returned = loan_info.collateral_amount - seized;
+            // get the owner before the loan is closed
+            let owner = LoanRef::owner_of(&loan_account, loan_id.clone()).unwrap_or(loan_info.borrower);
+            // close the loan, the owner deletes it with repay
+            LoanRef::liquidate_loan(&loan_account, loan_id)?;
+            self.update_collateral_debt(loan_info.collateral_token, loan_info.borrow_token, loan_info.borrow_amount, 0);
+            self.add_pool_collateral(loan_info.borrow_token, loan_info.collateral_token, seized);
+            // the loan is no longer lended, its value is in the seized collateral
+            let reserves_address = self.get_reserves_from_asset(loan_info.borrow_token)?;
+            BTokenRef::burn_builder(&reserves_address, self.env().account_id(), loan_info.borrow_amount)
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            // return net collateral to the owner
+            if returned > 0 {
+                PSP22Ref::transfer_builder(&loan_info.collateral_token, owner, returned, Vec::<u8>::new())
+                    .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                    .fire()
+                    .unwrap()?;
+                self.release_collateral(loan_info.collateral_token, returned);
+            }
+            Ok(returned)
+        }
+
+        /// # Returns
+        /// Returns a list of (token, Balance) paid to the caller with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `btoken_address` - AccountId of the btoken
+        /// * `btoken_amount` - Balance of btokens to be redeemed
+        ///
+        /// # Description
+        /// redeem_settlement burns btokens after shutdown and pays their pro-rata share of the asset left in the pool
+        /// and of every collateral seized by the market. It is possible once every loan of the market is settled
+        /// and every auction is closed, so no claim of the pool is outside of it. It ignores pauses.
+        #[ink(message)]
+        fn redeem_settlement(&mut self, btoken_address: AccountId, btoken_amount: Balance) -> Result<Vec<(AccountId, Balance)>, PoolManagerError> {
+            // save the address of the caller = address of the redeemer
+            let redeemer = self.env().caller();
+            let asset_address = self.get_asset_from_btoken(btoken_address)?;
+            if !self.is_shutdown() {
+                return Err(PoolManagerError::ProtocolNotShutdown)
+            }
+            if btoken_amount == 0 {
+                return Err(PoolManagerError::AmountNotSupported)
+            }
+            if PSP22Ref::balance_of(&btoken_address, redeemer) < btoken_amount {
+                return Err(PoolManagerError::InsufficientBalance)
+            }
+            // calculate the share before the btokens are burned
+            let shares = self.get_settlement_share(btoken_address, btoken_amount)?;
+            BTokenRef::burn_builder(&btoken_address, redeemer, btoken_amount)
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            for (index, (token, amount)) in shares.iter().enumerate() {
+                if *amount == 0 {
+                    continue
+                }
+                // seized collateral leaves the ledger of the market
+                if index > 0 {
+                    self.remove_pool_collateral(asset_address, *token, *amount)?;
+                }
+                PSP22Ref::transfer_builder(token, redeemer, *amount, Vec::<u8>::new())
+                    .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                    .fire()
+                    .unwrap()?;
+            }
+            Ok(shares)
+        }
+
+        /// # Returns
+        /// Returns a list of (token, Balance) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `btoken_address` - AccountId of the btoken
+        /// * `btoken_amount` - Balance of btokens
+        ///
+        /// # Description
+        /// get_settlement_share is helper function returning what the btokens would redeem now,
+        /// the asset of the pool comes first and seized collaterals follow. The asset held as collateral
+        /// for other markets is not counted. Shares are known only when the market has no lended asset left,
+        /// every loan is settled and every auction closed.
+        #[ink(message)]
+        fn get_settlement_share(&mut self, btoken_address: AccountId, btoken_amount: Balance) -> Result<Vec<(AccountId, Balance)>, PoolManagerError> {
+            let asset_address = self.get_asset_from_btoken(btoken_address)?;
+            // unsettled loans are claims of the pool which are not in the pool yet,
+            // reserve tokens of collateral bought by the pool are paid out as the collateral
+            let mut claims = 0;
+            for collateral_address in self.get_pool_collateral_tokens(asset_address) {
+                claims += self.get_pool_collateral_claim(asset_address, collateral_address);
+            }
+            if self.get_total_borrowed(asset_address)? > claims {
+                return Err(PoolManagerError::SettlementNotStarted)
+            }
+            let total_supply = PSP22Ref::total_supply(&btoken_address);
+            if total_supply == 0 {
+                return Ok(Vec::new())
+            }
+            let mut shares = Vec::new();
+            // the asset backing loans of other markets or seized by them is not part of the pool
+            let liquidity = self.get_pool_liquidity(asset_address).saturating_sub(self.get_held_collateral(asset_address));
+            shares.push((asset_address, liquidity * btoken_amount / total_supply));
+            for collateral_address in self.get_pool_collateral_tokens(asset_address) {
+                let collateral = self.get_pool_collateral(asset_address, collateral_address);
+                shares.push((collateral_address, collateral * btoken_amount / total_supply));
+            }
+            Ok(shares)
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the borrowed asset
+        /// * `collateral_address` - AccountId of the collateral
+        ///
+        /// # Description
+        /// get_pool_collateral is helper function returning the Balance of collateral seized by the market
+        #[ink(message)]
+        fn get_pool_collateral(&mut self, asset_address: AccountId, collateral_address: AccountId) -> Balance {
+            self.pool_collateral.get((&asset_address, &collateral_address)).unwrap_or(0)
+        }
+
+        /// # Returns
+        /// Returns a list of AccountIds
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the borrowed asset
+        ///
+        /// # Description
+        /// get_pool_collateral_tokens is helper function returning collaterals ever seized by the market
+        #[ink(message)]
+        fn get_pool_collateral_tokens(&mut self, asset_address: AccountId) -> Vec<AccountId> {
+            self.pool_collateral_tokens.get(&asset_address).unwrap_or_default()
+        }
+
+        /// # Returns
+        /// Returns a Balance with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `loan_id` - Id of the loan
+        ///
+        /// # Description
+        /// get_loan_debt is helper function returning how much is needed to repay the whole loan
+        #[ink(message)]
+        fn get_loan_debt(&mut self, loan_id: Id) -> Result<Balance, PoolManagerError> {
+            let loan_info = LoanRef::get_loan_info(&(self.loan_account), loan_id)?;
+            Ok(self.calculate_debt(&loan_info))
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `contract_address` - AccountId of btoken or loan contract owned by the protocol
+        /// * `new_owner` - AccountId of the new owner, it has to accept the ownership
+        ///
+        /// # Description
+        /// transfer_contract_ownership is ADMIN function to start a two-step ownership transfer of contract owned by the protocol
+        /// The ownership is not changed until new_owner calls accept_ownership on the contract.
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        fn transfer_contract_ownership(&mut self, contract_address: AccountId, new_owner: AccountId) -> Result<(), PoolManagerError> {
+            OwnableRef::transfer_ownership(&contract_address, new_owner)?;
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `contract_address` - AccountId of contract which ownership was transferred to the protocol
+        ///
+        /// # Description
+        /// accept_contract_ownership is ADMIN function to finish an ownership transfer of contract to the protocol
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        fn accept_contract_ownership(&mut self, contract_address: AccountId) -> Result<(), PoolManagerError> {
+            OwnableRef::accept_ownership(&contract_address)?;
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `contract_address` - AccountId of contract owned by the protocol
+        ///
+        /// # Description
+        /// cancel_contract_ownership_transfer is ADMIN function to cancel a pending ownership transfer, e.g. to a mistyped address
+        #[ink(message)]
+        #[modifiers(only_role(ADMIN))]
+        fn cancel_contract_ownership_transfer(&mut self, contract_address: AccountId) -> Result<(), PoolManagerError> {
+            OwnableRef::cancel_ownership_transfer(&contract_address)?;
+            Ok(())
+        }
+    }
+
+    /// This contract will contain constructor and helper functions for trait defined functions.
+    impl PoolManagerContract {
+        /// # Returns
+        /// Returns Self
+        ///
+        /// # Arguments
+        /// * `loan_hash` - hash of the loan deployed smart contract
+        /// * `btoken_hash` - hash of the btoken deployed smart contract
+        ///
+        /// # Description
+        /// Constructor for initializing our contract.
+        #[ink(constructor, payable)]
+        pub fn new(loan_hash: Hash, btoken_hash: Hash) -> Self{
+            // Use initialize_contract from ink_lang::codegen library.
+            ink_lang::codegen::initialize_contract(|instance: &mut PoolManagerContract| {
+                // to variable caller set AccountId that initialized the contract
+                let caller = instance.env().caller();
+                // function from AccessControl trait, caller is added to admin list
+                instance._init_with_admin(caller);
+                // set up the role hierarchy, oracle updaters are managed by risk admins
+                instance._set_role_admin(LISTING_ADMIN, ADMIN);
+                instance._set_role_admin(RISK_ADMIN, ADMIN);
+                instance._set_role_admin(ORACLE_UPDATER, RISK_ADMIN);
+                instance._set_role_admin(PAUSE_GUARDIAN, ADMIN);
+                instance._set_role_admin(TREASURY, ADMIN);
+                // caller gets every protocol role, so the roles can be handed over later
+                instance._setup_role(LISTING_ADMIN, caller);
+                instance._setup_role(RISK_ADMIN, caller);
+                instance._setup_role(ORACLE_UPDATER, caller);
+                instance._setup_role(PAUSE_GUARDIAN, caller);
+                instance._setup_role(TREASURY, caller);
+                // conversion rate of 0 would make every loan liquidatable
+                instance.parameter_bounds.insert(&ParameterKind::ConversionRate, &ParameterBounds { min: 1, max: Balance::MAX, max_change: 0, window: 0 });
+                // get hash of btoken contract to local struct
+                instance.btoken_contract_code_hash = btoken_hash;
+                // instantiate loan contract
+                let loan = LoanContractRef::new()
+                    .endowment(0)
+                    .code_hash(loan_hash)
+                    .salt_bytes(&[0xDE, 0xAD, 0xBE, 0xEF])
+                    .instantiate()
+                    .unwrap();
+                instance.loan_account = loan.to_account_id();
+            })
+        }
+
+        /// # Returns
+        /// Returns an AccountId
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `contract_name` optional String for specifying name in Metadata
+        /// * `contract_symbol` optional String for specifying symbol in Metadata
+        ///
+        /// # Description
+        /// instantiate_btoken_contract is helper function that creates instance of btoken with given data
+        fn instantiate_btoken_contract(&mut self, contract_name: &str, contract_symbol: &str) -> AccountId {
+            let code_hash = self.btoken_contract_code_hash;
+            let (hash, _) = ink_env::random::<ink_env::DefaultEnvironment>(contract_name.as_bytes()).expect("Failed to get salt");
+            let hash = hash.as_ref();
+            let contract = BTokenContractRef::new(Some(String::from(contract_name)), Some(String::from(contract_symbol)))
                     .endowment(0)
                     .code_hash(code_hash)
                     .salt_bytes(&hash[..4])
@@ -690,5 +3167,1257 @@ pub mod liquidity_pool_manager{
                     .unwrap();
             contract.to_account_id()
         }
+
+        /// # Returns
+        /// Returns an AccountId with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset from which we want to get its reserves token address
+        ///
+        /// # Description
+        /// get_reserves_from_asset is helper function
+        /// It is responsible for getting address of the token tracking how much of the asset is lended out.
+        fn get_reserves_from_asset(&mut self, asset_address: AccountId) -> Result<AccountId, PoolManagerError> {
+            // get reserves token address from asset address
+            let reserves_address = self.assets_lended.get(&asset_address).unwrap_or(ZERO_ADDRESS.into());
+            // check if we got something
+            if reserves_address.is_zero() {
+                return Err(PoolManagerError::AssetNotFound)
+            }
+            Ok(reserves_address)
+        }
+
+        /// # Returns
+        /// Returns a Balance with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset from which we want to get its borrowed Balance
+        ///
+        /// # Description
+        /// get_total_borrowed is helper function
+        /// It is responsible for getting how much of the asset is currently borrowed from its pool.
+        fn get_total_borrowed(&mut self, asset_address: AccountId) -> Result<Balance, PoolManagerError> {
+            let reserves_address = self.get_reserves_from_asset(asset_address)?;
+            Ok(PSP22Ref::balance_of(&reserves_address, self.env().account_id()))
+        }
+
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `collateral_address` - AccountId of collateral backing the loan
+        /// * `asset_address` - AccountId of the borrowed asset
+        /// * `previous_debt` - Balance of the loan debt before the change
+        /// * `new_debt` - Balance of the loan debt after the change
+        ///
+        /// # Description
+        /// update_collateral_debt is helper function that keeps total debt in the asset backed by the collateral in sync with its loans
+        fn update_collateral_debt(&mut self, collateral_address: AccountId, asset_address: AccountId, previous_debt: Balance, new_debt: Balance) {
+            let collateral_debt = self.get_collateral_debt(collateral_address, asset_address).saturating_sub(previous_debt) + new_debt;
+            self.collateral_debt.insert((&collateral_address, &asset_address), &collateral_debt);
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset whose market is checked
+        ///
+        /// # Description
+        /// check_market_active is helper function allowing actions which increase exposure only in active markets
+        fn check_market_active(&mut self, asset_address: AccountId) -> Result<(), PoolManagerError> {
+            // nothing increases exposure after shutdown
+            self.check_not_shutdown()?;
+            match self.get_market_state(asset_address) {
+                MarketState::Active => Ok(()),
+                MarketState::Frozen => Err(PoolManagerError::MarketFrozen),
+                MarketState::Paused => Err(PoolManagerError::MarketPaused),
+                MarketState::Delisted => Err(PoolManagerError::MarketDelisted),
+            }
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset whose market is checked
+        ///
+        /// # Description
+        /// check_market_not_paused is helper function letting users exit every market which is not paused
+        fn check_market_not_paused(&mut self, asset_address: AccountId) -> Result<(), PoolManagerError> {
+            if self.get_market_state(asset_address) == MarketState::Paused {
+                return Err(PoolManagerError::MarketPaused)
+            }
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns an AssetCategory
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `category_id` - Id of the category, NO_CATEGORY for default risk parameters
+        ///
+        /// # Description
+        /// get_risk_parameters is helper function returning risk parameters applied to loans of the category
+        fn get_risk_parameters(&mut self, category_id: CategoryId) -> AssetCategory {
+            self.get_category(category_id).unwrap_or(AssetCategory {
+                ltv: LTV,
+                liquidation_threshold: LIQUIDATION_THRESHOLD,
+                liquidation_bonus: LIQUIDATION_BONUS,
+                price_source: None,
+            })
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `collateral_address` - AccountId of the collateral asset
+        /// * `asset_address` - AccountId of the borrowed asset
+        /// * `amount` - Balance of the collateral
+        /// * `category` - risk parameters of the loan
+        ///
+        /// # Description
+        /// get_collateral_value is helper function returning value of the collateral in the borrowed asset.
+        /// Categories with price source value both assets against it, otherwise the direct conversion rate is used.
+        fn get_collateral_value(&mut self, collateral_address: AccountId, asset_address: AccountId, amount: Balance, category: &AssetCategory) -> Balance {
+            match category.price_source {
+                Some(price_source) => {
+                    let asset_rate = self.get_source_rate(asset_address, price_source);
+                    if asset_rate == 0 {
+                        return 0
+                    }
+                    self.get_source_rate(collateral_address, price_source) * amount / asset_rate
+                }
+                None => self.get_conversion_rate(collateral_address, asset_address, amount),
+            }
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the asset to be priced
+        /// * `price_source` - AccountId of the category price source
+        ///
+        /// # Description
+        /// get_source_rate is helper function returning how many price source units 1 asset unit is worth
+        fn get_source_rate(&mut self, asset_address: AccountId, price_source: AccountId) -> Balance {
+            if asset_address == price_source {
+                return 1
+            }
+            self.conversion_rates.get((&asset_address, &price_source)).unwrap_or(0)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `kind` - ParameterKind of the changed parameter
+        /// * `target` - what the parameter belongs to, e.g. asset address or category Id
+        /// * `previous` - current value of the parameter, None if it is not set or unlimited
+        /// * `value` - new value of the parameter, None means unlimited
+        ///
+        /// # Description
+        /// check_parameter_change is helper function enforcing the bounds of the parameter.
+        /// Relative change is measured from the value at the start of the window, so it can't be bypassed by many small updates.
+        fn check_parameter_change<T: scale::Encode>(&mut self, kind: ParameterKind, target: &T, previous: Option<Balance>, value: Option<Balance>) -> Result<(), PoolManagerError> {
+            // parameters without bounds can be set to any value
+            let bounds = match self.parameter_bounds.get(&kind) {
+                Some(bounds) => bounds,
+                None => return Ok(()),
+            };
+            // unlimited value is allowed only without upper bound
+            let new_value = value.unwrap_or(Balance::MAX);
+            if new_value < bounds.min || new_value > bounds.max {
+                return Err(PoolManagerError::ParameterOutOfBounds)
+            }
+            if bounds.max_change == 0 {
+                return Ok(())
+            }
+            // get the value at the start of the window, new window starts when the previous one passed
+            let mut key = [0u8; 32];
+            ink_env::hash_encoded::<ink_env::hash::Blake2x256, _>(&(kind, target), &mut key);
+            let now = self.env().block_timestamp();
+            let (window_start, base) = match self.parameter_history.get(&key) {
+                Some((start, base)) if now < start.saturating_add(bounds.window) => (start, base),
+                _ => (now, previous),
+            };
+            // relative change can't be measured from unset, unlimited or zero value
+            if let (Some(base), Some(value)) = (base, value) {
+                let change = if value > base { value - base } else { base - value };
+                if base > 0 && change.saturating_mul(BASIS_POINTS) > base.saturating_mul(bounds.max_change) {
+                    return Err(PoolManagerError::ParameterChangeTooLarge)
+                }
+            }
+            self.parameter_history.insert(&key, &(window_start, base));
+            Ok(())
+        }
+
+        /// # Returns
+        /// Without a return value
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset whose market is protected
+        /// * `asset_from` - AccountId of source asset of the conversion rate
+        /// * `asset_to` - AccountId of destination asset of the conversion rate
+        /// * `previous_rate` - conversion rate before the change, None if it was not set
+        /// * `exchange_rate` - new conversion rate
+        ///
+        /// # Description
+        /// check_price_deviation is helper function tripping the circuit breaker of the asset,
+        /// when the conversion rate moved more than allowed from the rate at the start of the price window.
+        fn check_price_deviation(&mut self, asset_address: AccountId, asset_from: AccountId, asset_to: AccountId, previous_rate: Option<Balance>, exchange_rate: Balance) {
+            let config = match self.circuit_breakers.get(&asset_address) {
+                Some(config) if config.max_price_deviation > 0 => config,
+                _ => return,
+            };
+            let key = (asset_address, asset_from, asset_to);
+            let now = self.env().block_timestamp();
+            // get the rate at the start of the window, new window starts when the previous one passed
+            let (window_start, reference) = match self.price_references.get(&key) {
+                Some((start, rate)) if now < start.saturating_add(config.price_window) => (start, rate),
+                _ => (now, previous_rate.unwrap_or(exchange_rate)),
+            };
+            let deviation = if exchange_rate > reference { exchange_rate - reference } else { reference - exchange_rate };
+            if reference > 0 && deviation.saturating_mul(BASIS_POINTS) > reference.saturating_mul(config.max_price_deviation) {
+                self.trip_circuit_breaker(asset_address, CircuitBreakerReason::PriceDeviation);
+            }
+            // while the market is paused, deviations are measured from the latest rate
+            if self.is_circuit_breaker_tripped(asset_address) {
+                self.price_references.insert(&key, &(now, exchange_rate));
+                return
+            }
+            self.price_references.insert(&key, &(window_start, reference));
+        }
+
+        /// # Returns
+        /// Without a return value
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset leaving the pool
+        /// * `amount` - Balance leaving the pool
+        ///
+        /// # Description
+        /// record_outflow is helper function adding the amount to the outflow of the current block.
+        /// If the outflow exceeds the maximum, the circuit breaker is tripped. The operation which crossed
+        /// the maximum still succeeds, so the trip is not reverted with it, every later outflow is paused.
+        /// Single outflows are capped by the rate limit of the pool.
+        fn record_outflow(&mut self, asset_address: AccountId, amount: Balance) {
+            let config = match self.circuit_breakers.get(&asset_address) {
+                Some(config) if config.max_block_outflow > 0 => config,
+                _ => return,
+            };
+            let block_number = self.env().block_number();
+            // outflow of previous blocks is not counted
+            let outflow = match self.block_outflows.get(&asset_address) {
+                Some((block, outflow)) if block == block_number => outflow,
+                _ => 0,
+            };
+            self.block_outflows.insert(&asset_address, &(block_number, outflow + amount));
+            if outflow + amount > config.max_block_outflow {
+                self.trip_circuit_breaker(asset_address, CircuitBreakerReason::Outflow);
+            }
+        }
+
+        /// # Returns
+        /// Without a return value
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset whose market is paused
+        /// * `reason` - CircuitBreakerReason why the market is paused
+        ///
+        /// # Description
+        /// trip_circuit_breaker is helper function pausing operations of the market until the guardian clears it
+        fn trip_circuit_breaker(&mut self, asset_address: AccountId, reason: CircuitBreakerReason) {
+            if self.is_circuit_breaker_tripped(asset_address) {
+                return
+            }
+            self.tripped_circuit_breakers.insert(&asset_address, &reason);
+            // remember which operations the circuit breaker paused, so clearing it keeps pauses of the guardian
+            let operations = CIRCUIT_BREAKER_OPERATIONS & !paused_operations(self, &Some(asset_address));
+            self.circuit_breaker_operations.insert(&asset_address, &operations);
+            if operations != 0 {
+                // pausing operations never fails
+                let _ = self._pause_operations::<PoolManagerError>(operations, Some(asset_address));
+            }
+            self.env().emit_event(CircuitBreakerTripped {
+                asset: asset_address,
+                reason,
+            });
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset
+        /// * `config` - RateLimitConfig of the asset
+        ///
+        /// # Description
+        /// get_available_outflow is helper function returning the capacity of the bucket refilled since the last outflow.
+        /// Empty bucket refills linearly over refill_period, bucket without refill period is always full.
+        fn get_available_outflow(&mut self, asset_address: AccountId, config: &RateLimitConfig) -> Balance {
+            let (last_update, available) = match self.rate_limit_buckets.get(&asset_address) {
+                Some(bucket) => bucket,
+                None => return config.capacity,
+            };
+            if config.refill_period == 0 {
+                return config.capacity
+            }
+            let elapsed = (self.env().block_timestamp() - last_update) as Balance;
+            let refill = config.capacity.saturating_mul(elapsed) / config.refill_period as Balance;
+            core::cmp::min(config.capacity, available.saturating_add(refill))
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of asset leaving the pool
+        /// * `amount` - Balance leaving the pool
+        ///
+        /// # Description
+        /// consume_outflow_capacity is helper function taking the amount from the bucket of the asset.
+        /// Every operation moving assets out of the pool has to call it.
+        fn consume_outflow_capacity(&mut self, asset_address: AccountId, amount: Balance) -> Result<(), PoolManagerError> {
+            let config = match self.rate_limits.get(&asset_address) {
+                Some(config) => config,
+                None => return Ok(()),
+            };
+            let available = self.get_available_outflow(asset_address, &config);
+            if amount > available {
+                return Err(PoolManagerError::RateLimitExceeded)
+            }
+            self.rate_limit_buckets.insert(&asset_address, &(self.env().block_timestamp(), available - amount));
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Balance of paid out asset with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the asset of the queue
+        /// * `max_requests` - maximum number of requests to be processed
+        ///
+        /// # Description
+        /// process_withdrawal_queue is helper function filling the requests from the head of the queue.
+        /// The first request which can't be filled completely is filled partially and the processing stops,
+        /// so the order of requests is kept. Paused withdrawals, circuit breaker and outflow rate limit are respected.
+        /// A request whose payout fails is parked and the processing continues with the next one.
+        fn process_withdrawal_queue(&mut self, asset_address: AccountId, max_requests: u32) -> Result<Balance, PoolManagerError> {
+            // requests are not filled while withdrawals are paused or after shutdown
+            if self.is_shutdown() || self.get_market_state(asset_address) == MarketState::Paused || operation_paused(self, WITHDRAW, &Some(asset_address)) {
+                return Ok(0)
+            }
+            let contract = self.env().account_id();
+            let btoken_address = self.get_btoken_from_asset(asset_address)?;
+            let mut head = self.queue_heads.get(&asset_address).unwrap_or(0);
+            let tail = self.queue_tails.get(&asset_address).unwrap_or(0);
+            let mut processed = 0;
+            let mut paid = 0;
+            while head < tail && processed < max_requests {
+                // a tripped circuit breaker stops the processing, the requests stay in the queue
+                if operation_paused(self, WITHDRAW, &Some(asset_address)) {
+                    break
+                }
+                processed += 1;
+                // skip canceled requests
+                let mut request = match self.withdrawal_requests.get((&asset_address, &head)) {
+                    Some(request) => request,
+                    None => {
+                        head += 1;
+                        self.queue_heads.insert(&asset_address, &head);
+                        continue
+                    }
+                };
+                // get the liquidity which can leave the pool
+                let total_asset = self.get_total_asset(asset_address)?;
+                let total_supply = PSP22Ref::total_supply(&btoken_address);
+                let mut available = self.get_pool_liquidity(asset_address);
+                if let Some(config) = self.rate_limits.get(&asset_address) {
+                    available = core::cmp::min(available, self.get_available_outflow(asset_address, &config));
+                }
+                // fill the whole request or as much of it as possible
+                let mut btoken_amount = request.btoken_amount;
+                let mut amount = btoken_amount * total_asset / total_supply;
+                if amount > available {
+                    btoken_amount = available * total_supply / total_asset;
+                    amount = btoken_amount * total_asset / total_supply;
+                }
+                if btoken_amount == 0 {
+                    break
+                }
+                // the request, the queue and the escrow are updated before the payout, so it is never paid twice
+                let original = request.clone();
+                let pending = self.get_pending_withdrawal(asset_address, request.owner);
+                self.pending_withdrawals.insert((&asset_address, &request.owner), &(pending - btoken_amount));
+                // partially filled request stays at the head of the queue
+                request.btoken_amount -= btoken_amount;
+                if request.btoken_amount > 0 {
+                    self.withdrawal_requests.insert((&asset_address, &head), &request);
+                } else {
+                    self.withdrawal_requests.remove((&asset_address, &head));
+                    self.queue_lengths.insert(&asset_address, &(self.get_queue_length(asset_address) - 1));
+                    self.queue_heads.insert(&asset_address, &(head + 1));
+                }
+                // unusual outflow in one block trips the circuit breaker
+                self.record_outflow(asset_address, amount);
+                self.consume_outflow_capacity(asset_address, amount)?;
+                // burn the escrowed btokens
+                BTokenRef::burn_builder(&btoken_address, contract, btoken_amount)
+                    .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                    .fire()
+                    .unwrap()?;
+                // give assets to the requester, the requester can't re-enter the pool during the payout
+                let payout = PSP22Ref::transfer_builder(&asset_address, original.owner, amount, Vec::<u8>::new()).fire();
+                if !matches!(payout, Ok(Ok(()))) {
+                    // the whole request is parked with its btokens escrowed again, the outflow stays counted
+                    BTokenRef::mint_builder(&btoken_address, contract, btoken_amount)
+                        .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                        .fire()
+                        .unwrap()?;
+                    self.pending_withdrawals.insert((&asset_address, &original.owner), &pending);
+                    if request.btoken_amount > 0 {
+                        self.withdrawal_requests.remove((&asset_address, &head));
+                        self.queue_lengths.insert(&asset_address, &(self.get_queue_length(asset_address) - 1));
+                    }
+                    self.parked_withdrawal_requests.insert((&asset_address, &head), &original);
+                    self.env().emit_event(WithdrawalRequestParked { asset: asset_address, owner: original.owner, request_id: head });
+                    head += 1;
+                    self.queue_heads.insert(&asset_address, &head);
+                    continue
+                }
+                paid += amount;
+                if request.btoken_amount > 0 {
+                    break
+                }
+                head += 1;
+            }
+            Ok(paid)
+        }
+
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the asset of the queue
+        ///
+        /// # Description
+        /// fill_withdrawal_queue is helper function filling the queue after lend, repay and other inflows.
+        /// An error of the processing doesn't fail the operation which brought the liquidity.
+        fn fill_withdrawal_queue(&mut self, asset_address: AccountId) {
+            let _ = self.process_withdrawal_queue(asset_address, AUTO_PROCESSED_REQUESTS);
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the asset
+        ///
+        /// # Description
+        /// get_pool_liquidity is helper function returning the Balance of the asset in the contract which belongs to the pool
+        fn get_pool_liquidity(&mut self, asset_address: AccountId) -> Balance {
+            PSP22Ref::balance_of(&asset_address, self.env().account_id()) - self.get_protocol_reserves(asset_address)
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `loan_info` - LoanInfo of the liquidated loan
+        /// * `reward` - Balance of the collateral given to the liquidator
+        /// * `category` - risk parameters of the loan
+        ///
+        /// # Description
+        /// calculate_shortfall is helper function returning the part of the borrowed amount
+        /// which is not backed by the collateral left in the contract after the liquidation
+        fn calculate_shortfall(&mut self, loan_info: &LoanInfo, reward: Balance, category: &AssetCategory) -> Balance {
+            let collateral_value = self.get_collateral_value(loan_info.collateral_token, loan_info.borrow_token, loan_info.collateral_amount.saturating_sub(reward), category);
+            loan_info.borrow_amount.saturating_sub(collateral_value)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `loan_id` - Id of the liquidated loan
+        /// * `asset_address` - AccountId of the borrowed asset
+        /// * `shortfall` - Balance of the debt not backed by collateral
+        ///
+        /// # Description
+        /// socialize_bad_debt is helper function removing the shortfall from the pool value.
+        /// Protocol reserves cover it first, they are moved to the pool. Then the safety module of the market is slashed.
+        /// The rest is written down by burning reserve tokens, so the btoken exchange rate drops
+        /// and lenders share the loss pro-rata.
+        fn socialize_bad_debt(&mut self, loan_id: Id, asset_address: AccountId, shortfall: Balance) -> Result<(), PoolManagerError> {
+            self.env().emit_event(BadDebtDetected { asset: asset_address, loan_id, shortfall });
+            let mut bad_debt = self.get_bad_debt(asset_address);
+            bad_debt.total_shortfall += shortfall;
+            // cover the shortfall from protocol reserves
+            let reserves = self.get_protocol_reserves(asset_address);
+            let covered = core::cmp::min(reserves, shortfall);
+            if covered > 0 {
+                self.protocol_reserves.insert(&asset_address, &(reserves - covered));
+                bad_debt.covered_by_reserves += covered;
+                self.env().emit_event(BadDebtCovered { asset: asset_address, amount: covered });
+            }
+            // the shortfall is no longer lended, covered part is in the pool and the rest is lost
+            let reserves_address = self.get_reserves_from_asset(asset_address)?;
+            BTokenRef::burn_builder(&reserves_address, self.env().account_id(), shortfall)
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            // slash the safety module for the rest
+            let mut remaining = shortfall - covered;
+            if remaining > 0 {
+                if let Some(safety_module) = self.get_safety_module(asset_address) {
+                    let covered = self.slash_safety_module(asset_address, safety_module, remaining)?;
+                    bad_debt.covered_by_safety_module += covered;
+                    remaining -= covered;
+                }
+            }
+            // what is left stays written down from the pool
+            if remaining > 0 {
+                bad_debt.written_down += remaining;
+                self.env().emit_event(BadDebtWrittenDown { asset: asset_address, amount: remaining });
+            }
+            self.bad_debts.insert(&asset_address, &bad_debt);
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Balance of covered bad debt with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the borrowed asset
+        /// * `safety_module` - AccountId of the safety module of the market
+        /// * `amount` - Balance of the asset which should be covered
+        ///
+        /// # Description
+        /// slash_safety_module is helper function slashing the stake to the contract.
+        /// Slashed asset stays in the pool, slashed btokens are burned, so their value goes to the remaining lenders.
+        fn slash_safety_module(&mut self, asset_address: AccountId, safety_module: AccountId, amount: Balance) -> Result<Balance, PoolManagerError> {
+            let contract = self.env().account_id();
+            let btoken_address = self.get_btoken_from_asset(asset_address)?;
+            let (slashed, covered) = if SafetyModuleRef::get_staked_token(&safety_module) == asset_address {
+                let slashed = SafetyModuleRef::slash_builder(&safety_module, amount, contract)
+                    .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                    .fire()
+                    .unwrap()?;
+                (slashed, slashed)
+            } else {
+                // convert the amount to btokens at the exchange rate after the write down, rounded up
+                let total_asset = self.get_total_asset(asset_address)?;
+                let total_supply = PSP22Ref::total_supply(&btoken_address);
+                if total_asset == 0 {
+                    return Ok(0)
+                }
+                let btoken_amount = (amount * total_supply + total_asset - 1) / total_asset;
+                let slashed = SafetyModuleRef::slash_builder(&safety_module, btoken_amount, contract)
+                    .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                    .fire()
+                    .unwrap()?;
+                if slashed == 0 {
+                    return Ok(0)
+                }
+                BTokenRef::burn_builder(&btoken_address, contract, slashed)
+                    .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                    .fire()
+                    .unwrap()?;
+                (slashed, core::cmp::min(amount, slashed * total_asset / total_supply))
+            };
+            if slashed > 0 {
+                self.env().emit_event(BadDebtCoveredBySafetyModule { asset: asset_address, safety_module, slashed, amount: covered });
+            }
+            Ok(covered)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        ///
+        /// # Description
+        /// check_not_shutdown is helper function stopping actions which are not possible after shutdown
+        fn check_not_shutdown(&mut self) -> Result<(), PoolManagerError> {
+            if self.is_shutdown() {
+                return Err(PoolManagerError::ProtocolShutdown)
+            }
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `loan_info` - LoanInfo of the loan
+        ///
+        /// # Description
+        /// calculate_debt is helper function returning the borrowed amount with interest,
+        /// interest stops accruing at shutdown
+        fn calculate_debt(&mut self, loan_info: &LoanInfo) -> Balance {
+            let now = match self.shutdown_timestamp {
+                Some(shutdown_timestamp) => core::cmp::min(shutdown_timestamp, self.env().block_timestamp()),
+                None => self.env().block_timestamp(),
+            };
+            // calculate time elapsed since loan creation
+            let timer = now.saturating_sub(loan_info.timestamp);
+            // calculate interest of the loan
+            let interest = (loan_info.interest_rate * timer as Balance) / YEAR as Balance;
+            (((loan_info.borrow_amount) * (10000 + interest)) / 10000) + 1
+        }
+
+        /// # Returns
+        /// Without a return value
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the borrowed asset
+        /// * `collateral_address` - AccountId of the seized collateral
+        /// * `amount` - Balance of the seized collateral
+        ///
+        /// # Description
+        /// add_pool_collateral is helper function assigning seized collateral to the market of the borrowed asset
+        fn add_pool_collateral(&mut self, asset_address: AccountId, collateral_address: AccountId, amount: Balance) {
+            if amount == 0 {
+                return
+            }
+            let mut tokens = self.get_pool_collateral_tokens(asset_address);
+            if !tokens.contains(&collateral_address) {
+                tokens.push(collateral_address);
+                self.pool_collateral_tokens.insert(&asset_address, &tokens);
+            }
+            let collateral = self.get_pool_collateral(asset_address, collateral_address);
+            self.pool_collateral.insert((&asset_address, &collateral_address), &(collateral + amount));
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the borrowed asset
+        /// * `collateral_address` - AccountId of the collateral
+        /// * `amount` - Balance of the collateral leaving the market
+        ///
+        /// # Description
+        /// remove_pool_collateral is helper function taking the collateral out of the ledger of the market
+        /// and burning the reserve tokens which kept its share of the price paid by the pool
+        fn remove_pool_collateral(&mut self, asset_address: AccountId, collateral_address: AccountId, amount: Balance) -> Result<(), PoolManagerError> {
+            let collateral = self.get_pool_collateral(asset_address, collateral_address);
+            let claim = self.get_pool_collateral_claim(asset_address, collateral_address);
+            let burned = if amount == collateral { claim } else { claim * amount / collateral };
+            self.pool_collateral.insert((&asset_address, &collateral_address), &(collateral - amount));
+            self.pool_collateral_claims.insert((&asset_address, &collateral_address), &(claim - burned));
+            self.release_collateral(collateral_address, amount);
+            if burned > 0 {
+                let reserves_address = self.get_reserves_from_asset(asset_address)?;
+                BTokenRef::burn_builder(&reserves_address, self.env().account_id(), burned)
+                    .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                    .fire()
+                    .unwrap()?;
+            }
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the borrowed asset
+        /// * `collateral_address` - AccountId of the collateral
+        ///
+        /// # Description
+        /// get_pool_collateral_claim is helper function returning reserve tokens kept for collateral bought by the pool
+        fn get_pool_collateral_claim(&mut self, asset_address: AccountId, collateral_address: AccountId) -> Balance {
+            self.pool_collateral_claims.get((&asset_address, &collateral_address)).unwrap_or(0)
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `token_address` - AccountId of the token
+        ///
+        /// # Description
+        /// get_held_collateral is helper function returning the Balance of the token in the contract which belongs to loans,
+        /// auctions or markets seizing it, it is not part of the pool of the token
+        fn get_held_collateral(&mut self, token_address: AccountId) -> Balance {
+            self.held_collateral.get(&token_address).unwrap_or(0)
+        }
+
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `token_address` - AccountId of the collateral
+        /// * `amount` - Balance of the collateral coming into the contract
+        ///
+        /// # Description
+        /// hold_collateral is helper function counting the collateral posted for a loan
+        fn hold_collateral(&mut self, token_address: AccountId, amount: Balance) {
+            let held = self.get_held_collateral(token_address);
+            self.held_collateral.insert(&token_address, &(held + amount));
+        }
+
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `token_address` - AccountId of the collateral
+        /// * `amount` - Balance of the collateral leaving the contract
+        ///
+        /// # Description
+        /// release_collateral is helper function removing collateral which left the contract from the held Balance
+        fn release_collateral(&mut self, token_address: AccountId, amount: Balance) {
+            let held = self.get_held_collateral(token_address);
+            self.held_collateral.insert(&token_address, &held.saturating_sub(amount));
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `loan_id` - Id of the liquidated loan
+        /// * `loan_info` - LoanInfo of the liquidated loan
+        /// * `collateral_amount` - Balance of the collateral to be auctioned
+        /// * `category` - risk parameters of the loan
+        ///
+        /// # Description
+        /// start_auction is helper function starting the auction of the collateral at the current oracle price
+        fn start_auction(&mut self, loan_id: Id, loan_info: &LoanInfo, collateral_amount: Balance, category: &AssetCategory) -> Result<(), PoolManagerError> {
+            let auction = Auction {
+                borrower: loan_info.borrower,
+                collateral_token: loan_info.collateral_token,
+                borrow_token: loan_info.borrow_token,
+                collateral_amount,
+                debt: self.calculate_debt(loan_info),
+                principal: loan_info.borrow_amount,
+                initial_collateral: collateral_amount,
+                start_value: self.get_collateral_value(loan_info.collateral_token, loan_info.borrow_token, collateral_amount, category),
+                start_time: self.env().block_timestamp(),
+                config: self.get_auction_config(loan_info.collateral_token),
+            };
+            self.env().emit_event(AuctionStarted {
+                collateral: auction.collateral_token,
+                loan_id: loan_id.clone(),
+                collateral_amount,
+                debt: auction.debt,
+                start_value: auction.start_value,
+            });
+            self.active_auctions.push(loan_id.clone());
+            // there is nothing to sell, the whole debt is bad debt
+            if collateral_amount == 0 {
+                return self.finish_auction(loan_id, auction)
+            }
+            LoanRef::update_loan(&(self.loan_account), loan_id.clone(), auction.principal, collateral_amount, auction.start_time)?;
+            self.auctions.insert(&loan_id, &auction);
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `loan_id` - Id of the liquidated loan
+        /// * `auction` - Auction to be finished
+        ///
+        /// # Description
+        /// finish_auction is helper function ending the auction. Collateral left after repaying the debt
+        /// is returned to the owner of the loan. Otherwise unsold collateral is seized by the market
+        /// and the borrowed amount not covered by its floor value is socialized as bad debt.
+        /// The rest of the borrowed amount is no longer lended, it is written down until the collateral is sold.
+        fn finish_auction(&mut self, loan_id: Id, auction: Auction) -> Result<(), PoolManagerError> {
+            let loan_account = self.loan_account;
+            let mut surplus = 0;
+            let mut shortfall = 0;
+            if auction.debt == 0 {
+                surplus = auction.collateral_amount;
+                if surplus > 0 {
+                    let owner = LoanRef::owner_of(&loan_account, loan_id.clone()).unwrap_or(auction.borrower);
+                    PSP22Ref::transfer_builder(&auction.collateral_token, owner, surplus, Vec::<u8>::new())
+                        .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                        .fire()
+                        .unwrap()?;
+                    self.release_collateral(auction.collateral_token, surplus);
+                }
+            } else {
+                let floor_value = self.calculate_auction_price(&auction, auction.collateral_amount, auction.config.floor);
+                self.add_pool_collateral(auction.borrow_token, auction.collateral_token, auction.collateral_amount);
+                shortfall = auction.principal.saturating_sub(floor_value);
+                // the value of the seized collateral returns to the pool when it is sold
+                let seized_principal = auction.principal - shortfall;
+                if seized_principal > 0 {
+                    let reserves_address = self.get_reserves_from_asset(auction.borrow_token)?;
+                    BTokenRef::burn_builder(&reserves_address, self.env().account_id(), seized_principal)
+                        .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                        .fire()
+                        .unwrap()?;
+                }
+            }
+            self.auctions.remove(&loan_id);
+            self.active_auctions.retain(|id| *id != loan_id);
+            // the loan is closed, its owner deletes it with repay
+            LoanRef::update_loan(&loan_account, loan_id.clone(), 0, 0, self.env().block_timestamp())?;
+            self.env().emit_event(AuctionFinished { loan_id: loan_id.clone(), surplus, shortfall });
+            if shortfall > 0 {
+                self.socialize_bad_debt(loan_id, auction.borrow_token, shortfall)?;
+            }
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `auction` - Auction of the collateral
+        ///
+        /// # Description
+        /// get_auction_factor is helper function returning the current price of the auction in basis points
+        /// of the oracle price, it declines linearly from the start premium to the floor
+        fn get_auction_factor(&mut self, auction: &Auction) -> Balance {
+            let elapsed = self.env().block_timestamp().saturating_sub(auction.start_time);
+            if elapsed >= auction.config.duration {
+                return auction.config.floor
+            }
+            let decline = (auction.config.start_premium - auction.config.floor) * elapsed as Balance / auction.config.duration as Balance;
+            auction.config.start_premium - decline
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `auction` - Auction of the collateral
+        /// * `collateral_amount` - Balance of the collateral
+        /// * `factor` - price in basis points of the oracle price
+        ///
+        /// # Description
+        /// calculate_auction_price is helper function returning the price of the collateral in the borrowed asset, rounded up
+        fn calculate_auction_price(&mut self, auction: &Auction, collateral_amount: Balance, factor: Balance) -> Balance {
+            if auction.initial_collateral == 0 {
+                return 0
+            }
+            let value = auction.start_value * collateral_amount / auction.initial_collateral;
+            (value * factor + BASIS_POINTS - 1) / BASIS_POINTS
+        }
+
+        /// # Returns
+        /// Returns a Balance in basis points
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `loan_info` - LoanInfo of the loan
+        /// * `category` - risk parameters of the loan
+        ///
+        /// # Description
+        /// calculate_health is helper function returning the collateral value at the liquidation threshold
+        /// divided by the debt with accrued interest
+        fn calculate_health(&mut self, loan_info: &LoanInfo, category: &AssetCategory) -> Balance {
+            let debt = self.calculate_debt(loan_info);
+            let value = self.get_collateral_value(loan_info.collateral_token, loan_info.borrow_token, loan_info.collateral_amount, category);
+            value * category.liquidation_threshold / 100 * BASIS_POINTS / debt
+        }
+
+        /// # Returns
+        /// Returns a Balance in basis points of the debt
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `collateral_address` - AccountId of the collateral
+        /// * `health` - health of the loan in basis points
+        /// * `category` - risk parameters of the loan
+        ///
+        /// # Description
+        /// calculate_liquidation_bonus is helper function returning the bonus from the curve of the collateral.
+        /// Collaterals without a curve use the flat bonus of the category.
+        fn calculate_liquidation_bonus(&mut self, collateral_address: AccountId, health: Balance, category: &AssetCategory) -> Balance {
+            let curve = match self.liquidation_bonus_curves.get(&collateral_address) {
+                Some(curve) => curve,
+                None => return category.liquidation_bonus * BASIS_POINTS / 100,
+            };
+            if health >= BASIS_POINTS {
+                return curve.min_bonus
+            }
+            if health <= curve.max_bonus_health {
+                return curve.max_bonus
+            }
+            curve.min_bonus + (curve.max_bonus - curve.min_bonus) * (BASIS_POINTS - health) / (BASIS_POINTS - curve.max_bonus_health)
+        }
+
+        /// # Returns
+        /// Returns a Balance of the collateral
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `loan_info` - LoanInfo of the loan
+        /// * `health` - health of the loan in basis points
+        /// * `category` - risk parameters of the loan
+        ///
+        /// # Description
+        /// calculate_liquidation_reward is helper function returning the collateral given to the liquidator.
+        /// The bonus is paid on the debt with interest, in collateral at the oracle price.
+        fn calculate_liquidation_reward(&mut self, loan_info: &LoanInfo, health: Balance, category: &AssetCategory) -> Balance {
+            let bonus = self.calculate_liquidation_bonus(loan_info.collateral_token, health, category);
+            let debt = self.calculate_debt(loan_info);
+            let value = self.get_collateral_value(loan_info.collateral_token, loan_info.borrow_token, loan_info.collateral_amount, category);
+            if value == 0 {
+                return 0
+            }
+            // the liquidator can never get more than the whole collateral
+            core::cmp::min(debt * bonus / BASIS_POINTS * loan_info.collateral_amount / value, loan_info.collateral_amount)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `liquidator` - AccountId receiving the liquidation bonus
+        /// * `loan_id` - Id of the loan to be liquidated
+        ///
+        /// # Description
+        /// liquidate is helper function liquidating the unhealthy loan and starting the auction of its collateral
+        fn liquidate(&mut self, liquidator: AccountId, loan_id: Id) -> Result<(), PoolManagerError> {
+            // get the loan account
+            let loan_account = self.loan_account;
+            // get loan info of the loan id
+            let loan_info = LoanRef::get_loan_info(&loan_account, loan_id.clone())?;
+            // if the loan is already liquidated, return error
+            if loan_info.already_liquidated {
+                return Err(PoolManagerError::LoanAlreadyLiquidated)
+            }
+            // after shutdown loans are closed through settle_loan
+            self.check_not_shutdown()?;
+            // check if the markets of the loan are not paused
+            self.check_market_not_paused(loan_info.borrow_token)?;
+            self.check_market_not_paused(loan_info.collateral_token)?;
+            check_operation_not_paused(self, LIQUIDATION, &Some(loan_info.borrow_token))?;
+            // get risk parameters the loan was created with
+            let category = self.get_risk_parameters(loan_info.category);
+            // check if the loan is unhealthy, the debt with interest is above the liquidation threshold of the collateral
+            let health = self.calculate_health(&loan_info, &category);
+            // fixed-term loans are liquidatable after maturity plus grace period regardless of price
+            if health < BASIS_POINTS || self.is_loan_expired(&loan_info) {
+                // calculate reward for liquidating, it grows as the health of the loan deteriorates
+                let reward = self.calculate_liquidation_reward(&loan_info, health, &category);
+                // transfer collateral to liquidator
+                PSP22Ref::transfer_builder(&loan_info.collateral_token, liquidator, reward,Vec::<u8>::new())
+                    .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                    .fire()
+                    .unwrap()?;
+                self.release_collateral(loan_info.collateral_token, reward);
+                // use liquidation loan function from Loan
+                LoanRef::liquidate_loan(&loan_account, loan_id.clone())?;
+                // remove the loan from the debt backed by the collateral
+                self.update_collateral_debt(loan_info.collateral_token, loan_info.borrow_token, loan_info.borrow_amount, 0);
+                // the rest of the collateral is sold in the auction repaying the debt
+                self.start_auction(loan_id, &loan_info, loan_info.collateral_amount.saturating_sub(reward), &category)?;
+            }
+            else {
+                return Err(PoolManagerError::LoanUnliquidable)
+            }
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Balance of bought collateral with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `bidder` - AccountId paying for the collateral
+        /// * `loan_id` - Id of the liquidated loan
+        /// * `collateral_amount` - maximum Balance of collateral the bidder wants to buy
+        ///
+        /// # Description
+        /// buy_collateral is helper function buying collateral in the auction at its current price
+        fn buy_collateral(&mut self, bidder: AccountId, loan_id: Id, collateral_amount: Balance) -> Result<Balance, PoolManagerError> {
+            // get the address of the contract
+            let contract = self.env().account_id();
+            let mut auction = self.auctions.get(&loan_id).ok_or(PoolManagerError::AuctionNotFound)?;
+            // check if the markets of the loan are not paused
+            self.check_market_not_paused(auction.borrow_token)?;
+            self.check_market_not_paused(auction.collateral_token)?;
+            check_operation_not_paused(self, LIQUIDATION, &Some(auction.borrow_token))?;
+            // calculate the price of the collateral, buying more than the debt is worth is not possible
+            let mut amount = core::cmp::min(collateral_amount, auction.collateral_amount);
+            let factor = self.get_auction_factor(&auction);
+            let mut cost = self.calculate_auction_price(&auction, amount, factor);
+            if cost > auction.debt {
+                amount = amount * auction.debt / cost;
+                cost = auction.debt;
+            }
+            if amount == 0 || cost == 0 {
+                return Err(PoolManagerError::AmountNotSupported)
+            }
+            // check if the allowance and balance of the bidder are sufficient
+            if PSP22Ref::allowance(&auction.borrow_token, bidder, contract) < cost {
+                return Err(PoolManagerError::InsufficientAllowance)
+            }
+            if PSP22Ref::balance_of(&auction.borrow_token, bidder) < cost {
+                return Err(PoolManagerError::InsufficientBalance)
+            }
+            // exchange the payment for the collateral
+            PSP22Ref::transfer_from_builder(&auction.borrow_token, bidder, contract, cost, Vec::<u8>::new())
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            PSP22Ref::transfer_builder(&auction.collateral_token, bidder, amount, Vec::<u8>::new())
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            self.release_collateral(auction.collateral_token, amount);
+            // burn the reserve tokens of the repaid part of the borrowed amount
+            let repaid_principal = if cost == auction.debt {
+                auction.principal
+            } else {
+                auction.principal * cost / auction.debt
+            };
+            let reserves_address = self.get_reserves_from_asset(auction.borrow_token)?;
+            BTokenRef::burn_builder(&reserves_address, contract, repaid_principal)
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            auction.debt -= cost;
+            auction.principal -= repaid_principal;
+            auction.collateral_amount -= amount;
+            self.env().emit_event(AuctionBid { bidder, loan_id: loan_id.clone(), collateral_amount: amount, paid: cost });
+            let borrow_token = auction.borrow_token;
+            if auction.debt == 0 || auction.collateral_amount == 0 {
+                self.finish_auction(loan_id, auction)?;
+            } else {
+                LoanRef::update_loan(&(self.loan_account), loan_id.clone(), auction.principal, auction.collateral_amount, self.env().block_timestamp())?;
+                self.auctions.insert(&loan_id, &auction);
+            }
+            // proceeds fill waiting withdrawal requests
+            self.fill_withdrawal_queue(borrow_token);
+            Ok(amount)
+        }
+
+        /// # Returns
+        /// Returns a LiquidationResult with outcomes of the liquidation and of the purchase, None if nothing was bought
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `liquidator` - AccountId of the keeper
+        /// * `loan_id` - Id of the loan to be liquidated
+        /// * `max_repay` - maximum Balance of the borrowed asset the keeper pays in the auction
+        ///
+        /// # Description
+        /// liquidate_and_buy is helper function liquidating the loan and buying as much collateral
+        /// as max_repay pays for in the started auction. The payment is checked before the liquidation,
+        /// so the purchase doesn't fail on the transfer after the loan was liquidated.
+        fn liquidate_and_buy(&mut self, liquidator: AccountId, loan_id: Id, max_repay: Balance) -> LiquidationResult {
+            let payment = if max_repay > 0 {
+                self.check_payment(liquidator, loan_id.clone(), max_repay)
+            } else {
+                Ok(())
+            };
+            // liquidate fails before changing anything for healthy or already liquidated loans
+            let liquidation = self.liquidate(liquidator, loan_id.clone());
+            if liquidation.is_err() || max_repay == 0 {
+                return LiquidationResult { liquidation, purchase: None }
+            }
+            if let Err(error) = payment {
+                return LiquidationResult { liquidation, purchase: Some(Err(error)) }
+            }
+            // the auction is already finished when there was no collateral to sell
+            let auction = match self.auctions.get(&loan_id) {
+                Some(auction) => auction,
+                None => return LiquidationResult { liquidation, purchase: None },
+            };
+            let factor = self.get_auction_factor(&auction);
+            let cost = self.calculate_auction_price(&auction, auction.collateral_amount, factor);
+            let amount = if cost <= max_repay {
+                auction.collateral_amount
+            } else {
+                auction.collateral_amount * max_repay / cost
+            };
+            if amount == 0 {
+                return LiquidationResult { liquidation, purchase: None }
+            }
+            LiquidationResult { liquidation, purchase: Some(self.buy_collateral(liquidator, loan_id, amount)) }
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `payer` - AccountId paying in the borrowed asset of the loan
+        /// * `loan_id` - Id of the loan
+        /// * `amount` - Balance of the borrowed asset to be paid
+        ///
+        /// # Description
+        /// check_payment is helper function checking the allowance and balance of the payer in the borrowed asset of the loan
+        fn check_payment(&mut self, payer: AccountId, loan_id: Id, amount: Balance) -> Result<(), PoolManagerError> {
+            let loan_info = LoanRef::get_loan_info(&(self.loan_account), loan_id)?;
+            if PSP22Ref::allowance(&loan_info.borrow_token, payer, self.env().account_id()) < amount {
+                return Err(PoolManagerError::InsufficientAllowance)
+            }
+            if PSP22Ref::balance_of(&loan_info.borrow_token, payer) < amount {
+                return Err(PoolManagerError::InsufficientBalance)
+            }
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and PoolManagerError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `asset_address` - AccountId of the asset that is borrowed
+        /// * `collateral_address` - AccountId of the asset that is colateralized
+        /// * `amount` - the Balance of how much asset is borrowed
+        /// * `term` - optional time until maturity, None for open-ended loans
+        ///
+        /// # Description
+        /// create_borrow is helper function which handles the process of borrowing assets from the contract
+        fn create_borrow(&mut self, asset_address: AccountId, collateral_address: AccountId, amount: Balance, term: Option<Timestamp>) -> Result<(), PoolManagerError> {
+            // check if both markets accept new borrows
+            self.check_market_active(asset_address)?;
+            self.check_market_active(collateral_address)?;
+            // get the address of the caller = address of the borrower
+            let borrower = self.env().caller();
+            // get the address of the contract
+            let contract = self.env().account_id();
+            // get the allowance for the contract
+            let allowance = PSP22Ref::allowance(&collateral_address, borrower, contract);
+            // check if the allowance is sufficient
+            if allowance < amount{
+                return Err(PoolManagerError::InsufficientAllowance)
+            }
+            let user_balance = PSP22Ref::balance_of(&collateral_address, borrower);
+            // check if the balance is sufficient
+            if user_balance < amount {
+                return Err(PoolManagerError::InsufficientBalance)
+            }
+            // check if the collateral is accepted
+            if !self.get_collateral_acceptance(collateral_address) {
+                return Err(PoolManagerError::CollateralNotSupported)
+            }
+            // get the reserves token address tracking the lended asset
+            let reserves_address = self.get_reserves_from_asset(asset_address)?;
+            // borrowers in efficiency mode can only use assets from their category
+            let category_id = self.get_user_category(borrower);
+            if category_id != NO_CATEGORY
+                && (self.get_asset_category(asset_address) != category_id
+                    || self.get_asset_category(collateral_address) != category_id)
+            {
+                return Err(PoolManagerError::AssetNotInCategory)
+            }
+            let category = self.get_risk_parameters(category_id);
+            // fixed-term loans get the rate of their term
+            let (maturity, interest_rate) = match term {
+                Some(term) => {
+                    let interest_rate = self.get_term_rate(term).ok_or(PoolManagerError::TermNotSupported)?;
+                    (Some(self.env().block_timestamp() + term), interest_rate)
+                }
+                None => (None, APY),
+            };
+            // calculate the deposited collateral price
+            let price = self.get_collateral_value(collateral_address, asset_address, amount, &category);
+            // set the liquidation price to the liquidation threshold
+            let liquidation_price = price * category.liquidation_threshold / 100;
+            // set the borrowed amount to the loan to value
+            let borrow_amount = price * category.ltv / 100;
+            // to be sure, check if borrow_ammount is not greater equal to liquidation price
+            if borrow_amount >= liquidation_price {
+                return Err(PoolManagerError::AmountNotSupported)
+            }
+            let asset_balance = self.get_pool_liquidity(asset_address);
+            if asset_balance < borrow_amount{
+                return Err(PoolManagerError::InsufficientPoolBalance)
+            }
+            // check if the borrowed amount fits under the borrow cap
+            if let Some(borrow_cap) = self.borrow_caps.get(&asset_address) {
+                if self.get_total_borrowed(asset_address)? + borrow_amount > borrow_cap {
+                    return Err(PoolManagerError::BorrowCapExceeded)
+                }
+            }
+            // isolated collateral can only back configured assets up to its debt ceiling
+            if self.is_collateral_isolated(collateral_address) {
+                let debt_ceiling = self.get_debt_ceiling(collateral_address, asset_address)
+                    .ok_or(PoolManagerError::AssetNotBorrowableInIsolation)?;
+                if self.get_collateral_debt(collateral_address, asset_address) + borrow_amount > debt_ceiling {
+                    return Err(PoolManagerError::DebtCeilingExceeded)
+                }
+            }
+            // check the outflow rate limit of the pool
+            self.consume_outflow_capacity(asset_address, borrow_amount)?;
+            // unusual outflow in one block trips the circuit breaker
+            self.record_outflow(asset_address, borrow_amount);
+            // transfer the collateral to the users address
+            PSP22Ref::transfer_from_builder(&collateral_address, borrower, contract, amount, Vec::<u8>::new())
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            self.hold_collateral(collateral_address, amount);
+            // create loan info
+            let loan_info = LoanInfo{
+                borrower: borrower,
+                collateral_token: collateral_address,
+                collateral_amount: amount,
+                borrow_token: asset_address,
+                borrow_amount: borrow_amount,
+                liquidation_price: liquidation_price,
+                timestamp: self.env().block_timestamp(),
+                already_liquidated: false,
+                category: category_id,
+                maturity: maturity,
+                interest_rate: interest_rate,
+            };
+            // create loan
+            LoanRef::create_loan(&(self.loan_account), loan_info)?;
+            // add the loan to the debt backed by the collateral
+            self.update_collateral_debt(collateral_address, asset_address, 0, borrow_amount);
+            // transfer the asset to the borrower
+            PSP22Ref::transfer_builder(&asset_address, borrower, borrow_amount, Vec::<u8>::new())
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            // mint borrow amount of the reserve token
+            BTokenRef::mint_builder(&reserves_address, contract, borrow_amount)
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a bool, true if the loan is past its maturity plus grace period
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `loan_info` - info of the loan
+        ///
+        /// # Description
+        /// is_loan_expired is helper function checking if the fixed-term loan is liquidatable regardless of price
+        fn is_loan_expired(&mut self, loan_info: &LoanInfo) -> bool {
+            match loan_info.maturity {
+                Some(maturity) => maturity + self.get_grace_period() < self.env().block_timestamp(),
+                None => false,
+            }
+        }
     }
 }
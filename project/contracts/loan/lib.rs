@@ -6,6 +6,7 @@
 pub mod loan {
     /// imports of libraries and traits needed
     use ink_prelude::string::String;
+    use ink_prelude::vec::Vec;
     use ink_storage::Mapping;
     use ink_storage::traits::SpreadAllocate;
     use brush::modifiers;
@@ -49,6 +50,7 @@ pub mod loan {
         /// # Description
         /// create_loan function is declared in trait Loan and its function is to create new loan
         /// according to data received from arguments and storing info about it.
+        #[modifiers(only_owner)]
         #[ink(message)]
         /// Only_owner modifier is used to access this function just to authorized user.
         fn create_loan(&mut self, loan_info: LoanInfo) -> Result<(), PSP34Error> {
@@ -77,6 +79,7 @@ pub mod loan {
         /// delete_loan function is declared in trait Loan
         /// its function is to delete outdated loan burn PSP34 token from the owner
         /// Only_owner modifier is used to access this function just to authorized user.
+        #[modifiers(only_owner)]
         #[ink(message)]
         fn delete_loan(&mut self, caller: AccountId, loan_id: Id) -> Result<(), PSP34Error> {
             // We delete loan from the mapping.
@@ -100,6 +103,7 @@ pub mod loan {
         /// update_loan function is declared in trait Loan
         /// its function is to update loan info after repay action
         /// Only_owner modifier is used to access this function just to authorized user.
+        #[modifiers(only_owner)]
         #[ink(message)]
         fn update_loan(&mut self, loan_id: Id, borrow_amount: Balance, collateral_amount: Balance, timestamp: Timestamp) -> Result<(), PSP34Error> {
             // We call helpers function which updates info needed to be updated.
@@ -126,6 +130,30 @@ pub mod loan {
             self.liquidate_loan_internal(loan_id)
         }
 
+        /// # Returns
+        /// Returns a Ok(()) with success and PSP34Error otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `loan_id` Id of loan to be changed
+        /// * `loan_info` new info of the loan
+        ///
+        /// # Description
+        /// set_loan_info function is declared in trait Loan
+        /// its function is to replace info of the loan, e.g. after collateral swap, while the loan keeps its Id and owner
+        /// Only_owner modifier is used to access this function just to authorized user.
+        #[modifiers(only_owner)]
+        #[ink(message)]
+        fn set_loan_info(&mut self, loan_id: Id, loan_info: LoanInfo) -> Result<(), PSP34Error> {
+            // Check if the loan exists.
+            if self.existing_loan_list.get(&loan_id).is_none() {
+                return Err(PSP34Error::Custom(String::from("Loan with this Id does not exist!")))
+            }
+            // Replace the loan in the mapping.
+            self.existing_loan_list.insert(&loan_id, &loan_info);
+            Ok(())
+        }
+
         /// # Returns
         /// Returns a LoanInfo with success and PSP34Error otherwise
         ///
@@ -147,6 +175,37 @@ pub mod loan {
             // Return unwrapped loan info.
             Ok(loan_info.unwrap())
         }
+
+        /// # Returns
+        /// Returns a list of pairs (Id, LoanInfo)
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `offset` number of loan Ids to skip
+        /// * `limit` maximum number of loan Ids to scan
+        ///
+        /// # Description
+        /// get_loan_list function is declared in trait Loan
+        /// its function is to page through existing loans, deleted loans in the scanned range are skipped
+        #[ink(message)]
+        fn get_loan_list(&self, offset: u32, limit: u32) -> Vec<(Id, LoanInfo)> {
+            let mut loans = Vec::new();
+            // Get the highest used Id.
+            let last = match self.last_loan_id {
+                Id::U8(v) => v as u32,
+                _ => return loans,
+            };
+            // Loan Ids start at 2, Id 1 holds attributes of the collection.
+            let first = offset.saturating_add(2);
+            let end = core::cmp::min(first.saturating_add(limit), last + 1);
+            for index in first..end {
+                let loan_id = Id::U8(index as u8);
+                if let Some(loan_info) = self.existing_loan_list.get(&loan_id) {
+                    loans.push((loan_id, loan_info));
+                }
+            }
+            loans
+        }
     }
 
     /// This contract will contain constructor and helper functions for trait defined functions.
@@ -163,6 +222,8 @@ pub mod loan {
                 // Set last loan id to 1.
                 instance.last_loan_id = Id::U8(1u8);
                 instance._set_attribute(Id::U8(1u8), String::from("LoanPSP34").into_bytes(), String::from("LPSP34").into_bytes());
+                // Set the caller (liquidity pool manager) as owner of the contract.
+                instance._init_with_owner(instance.env().caller());
             })
         }
 
@@ -1,8 +1,16 @@
 #[cfg(feature = "btoken")]
 pub mod btoken;
+#[cfg(feature = "governance_token")]
+pub mod governance_token;
+#[cfg(feature = "governor")]
+pub mod governor;
 #[cfg(feature = "liquidity_pool_manager")]
 pub mod liquidity_pool_manager;
 #[cfg(feature = "loan")]
 pub mod loan;
+#[cfg(feature = "multisig")]
+pub mod multisig;
+#[cfg(feature = "safety_module")]
+pub mod safety_module;
 #[cfg(feature = "stablecoin")]
 pub mod stablecoin;
@@ -0,0 +1,357 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+/// This contract is M-of-N multisig wallet for administration of the protocol.
+/// Owners submit and confirm encoded calls of other contracts, e.g. PoolManagerRef,
+/// call is executed once it has threshold confirmations.
+/// The multisig is supposed to be granted the ADMIN role of the liquidity pool manager.
+#[brush::contract]
+pub mod multisig {
+    /// imports of libraries and traits needed
+    use ink_storage::traits::SpreadAllocate;
+    use ink_storage::Mapping;
+    use ink_lang::codegen::Env;
+    use ink_prelude::vec::Vec;
+    use liquidity_pool_protocol::traits::multisig::*;
+    use liquidity_pool_protocol::traits::transaction::*;
+
+    /// Defining storage and its components and also deriving needed imports to our storage.
+    #[ink(storage)]
+    #[derive(Default, SpreadAllocate)]
+    /// Our contracts variables are stored in this struct.
+    pub struct MultisigContract {
+        /// list of owners of the multisig
+        owners: Vec<AccountId>,
+        /// number of confirmations needed to execute a transaction
+        threshold: u32,
+        /// Id of last submitted transaction
+        last_transaction_id: TransactionId,
+        /// mapping from transaction Id to transaction
+        transactions: Mapping<TransactionId, Transaction>,
+        /// mapping of owners which confirmed the transaction
+        confirmations: Mapping<(TransactionId, AccountId), ()>,
+        /// mapping of already executed transactions
+        executed: Mapping<TransactionId, ()>,
+    }
+
+    /// We implement functions declared in Multisig trait.
+    impl Multisig for MultisigContract {
+        /// # Returns
+        /// Returns a TransactionId with success and MultisigError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `transaction` - encoded call of another contract
+        ///
+        /// # Description
+        /// submit_transaction is owner function to propose a new transaction, it is confirmed by the submitter.
+        #[ink(message)]
+        fn submit_transaction(&mut self, transaction: Transaction) -> Result<TransactionId, MultisigError> {
+            let caller = self.env().caller();
+            self.check_owner(caller)?;
+            // store the transaction under new Id
+            self.last_transaction_id += 1;
+            let transaction_id = self.last_transaction_id;
+            self.transactions.insert(&transaction_id, &transaction);
+            // submitter confirms the transaction
+            self.confirmations.insert((&transaction_id, &caller), &());
+            Ok(transaction_id)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and MultisigError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `transaction_id` - Id of transaction
+        ///
+        /// # Description
+        /// confirm_transaction is owner function to confirm a transaction which was not executed yet.
+        #[ink(message)]
+        fn confirm_transaction(&mut self, transaction_id: TransactionId) -> Result<(), MultisigError> {
+            let caller = self.env().caller();
+            self.check_owner(caller)?;
+            self.check_pending(transaction_id)?;
+            if self.is_confirmed_by(transaction_id, caller) {
+                return Err(MultisigError::AlreadyConfirmed)
+            }
+            self.confirmations.insert((&transaction_id, &caller), &());
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and MultisigError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `transaction_id` - Id of transaction
+        ///
+        /// # Description
+        /// revoke_confirmation is owner function to take back its confirmation of a transaction which was not executed yet.
+        #[ink(message)]
+        fn revoke_confirmation(&mut self, transaction_id: TransactionId) -> Result<(), MultisigError> {
+            let caller = self.env().caller();
+            self.check_owner(caller)?;
+            self.check_pending(transaction_id)?;
+            if !self.is_confirmed_by(transaction_id, caller) {
+                return Err(MultisigError::NotConfirmed)
+            }
+            self.confirmations.remove((&transaction_id, &caller));
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and MultisigError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `transaction_id` - Id of transaction
+        ///
+        /// # Description
+        /// execute_transaction is owner function to call the transaction once it has threshold confirmations.
+        /// Result of the called message is not decoded, only failed calls are reported.
+        #[ink(message, payable)]
+        fn execute_transaction(&mut self, transaction_id: TransactionId) -> Result<(), MultisigError> {
+            self.check_owner(self.env().caller())?;
+            self.check_pending(transaction_id)?;
+            if self.get_confirmation_count(transaction_id) < self.threshold {
+                return Err(MultisigError::ThresholdNotReached)
+            }
+            // mark transaction executed before the call, so it can't be executed again by reentrancy
+            self.executed.insert(&transaction_id, &());
+            let transaction = self.transactions.get(&transaction_id).unwrap();
+            call_transaction(&transaction).map_err(|_| MultisigError::TransactionFailed)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and MultisigError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `owner` - AccountId of new owner
+        ///
+        /// # Description
+        /// add_owner function adds a new owner, it can be called only by the multisig itself through a transaction.
+        #[ink(message)]
+        fn add_owner(&mut self, owner: AccountId) -> Result<(), MultisigError> {
+            self.check_multisig()?;
+            if self.is_owner(owner) {
+                return Err(MultisigError::OwnerAlreadyExists)
+            }
+            self.owners.push(owner);
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and MultisigError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `owner` - AccountId of removed owner
+        ///
+        /// # Description
+        /// remove_owner function removes an owner, it can be called only by the multisig itself through a transaction.
+        /// There have to stay at least threshold owners, confirmations of removed owner are not counted anymore.
+        #[ink(message)]
+        fn remove_owner(&mut self, owner: AccountId) -> Result<(), MultisigError> {
+            self.check_multisig()?;
+            let index = self.owners.iter().position(|o| *o == owner).ok_or(MultisigError::OwnerNotFound)?;
+            if self.owners.len() as u32 - 1 < self.threshold {
+                return Err(MultisigError::InvalidThreshold)
+            }
+            self.owners.swap_remove(index);
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and MultisigError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `threshold` - new number of confirmations needed, between 1 and number of owners
+        ///
+        /// # Description
+        /// change_threshold function changes the threshold, it can be called only by the multisig itself through a transaction.
+        #[ink(message)]
+        fn change_threshold(&mut self, threshold: u32) -> Result<(), MultisigError> {
+            self.check_multisig()?;
+            if threshold == 0 || threshold > self.owners.len() as u32 {
+                return Err(MultisigError::InvalidThreshold)
+            }
+            self.threshold = threshold;
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Vec of AccountIds
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        ///
+        /// # Description
+        /// get_owners function returns list of owners.
+        #[ink(message)]
+        fn get_owners(&self) -> Vec<AccountId> {
+            self.owners.clone()
+        }
+
+        /// # Returns
+        /// Returns an u32
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        ///
+        /// # Description
+        /// get_threshold function returns number of confirmations needed to execute a transaction.
+        #[ink(message)]
+        fn get_threshold(&self) -> u32 {
+            self.threshold
+        }
+
+        /// # Returns
+        /// Returns a bool
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `account` - AccountId to be checked
+        ///
+        /// # Description
+        /// is_owner function returns whether account is an owner.
+        #[ink(message)]
+        fn is_owner(&self, account: AccountId) -> bool {
+            self.owners.contains(&account)
+        }
+
+        /// # Returns
+        /// Returns an Option of Transaction
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `transaction_id` - Id of transaction
+        ///
+        /// # Description
+        /// get_transaction function returns transaction by its Id.
+        #[ink(message)]
+        fn get_transaction(&self, transaction_id: TransactionId) -> Option<Transaction> {
+            self.transactions.get(&transaction_id)
+        }
+
+        /// # Returns
+        /// Returns an u32
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `transaction_id` - Id of transaction
+        ///
+        /// # Description
+        /// get_confirmation_count function returns number of current owners which confirmed the transaction.
+        #[ink(message)]
+        fn get_confirmation_count(&self, transaction_id: TransactionId) -> u32 {
+            self.owners.iter().filter(|owner| self.is_confirmed_by(transaction_id, **owner)).count() as u32
+        }
+
+        /// # Returns
+        /// Returns a bool
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `transaction_id` - Id of transaction
+        /// * `owner` - AccountId of owner
+        ///
+        /// # Description
+        /// is_confirmed_by function returns whether owner confirmed the transaction.
+        #[ink(message)]
+        fn is_confirmed_by(&self, transaction_id: TransactionId, owner: AccountId) -> bool {
+            self.confirmations.get((&transaction_id, &owner)).is_some()
+        }
+
+        /// # Returns
+        /// Returns a bool
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `transaction_id` - Id of transaction
+        ///
+        /// # Description
+        /// is_executed function returns whether the transaction was already executed.
+        #[ink(message)]
+        fn is_executed(&self, transaction_id: TransactionId) -> bool {
+            self.executed.get(&transaction_id).is_some()
+        }
+    }
+
+    /// This contract will contain constructor and helper functions for trait defined functions.
+    impl MultisigContract {
+        /// # Returns
+        /// Returns Self
+        ///
+        /// # Arguments
+        /// * `owners` - Vec of unique owners
+        /// * `threshold` - number of confirmations needed, between 1 and number of owners
+        ///
+        /// # Description
+        /// Constructor for initializing our contract.
+        #[ink(constructor, payable)]
+        pub fn new(owners: Vec<AccountId>, threshold: u32) -> Self {
+            // Use initialize_contract from ink_lang::codegen library.
+            ink_lang::codegen::initialize_contract(|instance: &mut MultisigContract| {
+                assert!(threshold > 0 && threshold <= owners.len() as u32);
+                for owner in owners.iter() {
+                    assert!(!instance.is_owner(*owner), "Duplicate owner");
+                    instance.owners.push(*owner);
+                }
+                instance.threshold = threshold;
+            })
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and MultisigError otherwise
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `account` - AccountId to be checked
+        ///
+        /// # Description
+        /// Helper function to check if account is an owner.
+        fn check_owner(&self, account: AccountId) -> Result<(), MultisigError> {
+            if !self.is_owner(account) {
+                return Err(MultisigError::NotAnOwner)
+            }
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and MultisigError otherwise
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        ///
+        /// # Description
+        /// Helper function to check if the caller is the multisig itself.
+        fn check_multisig(&self) -> Result<(), MultisigError> {
+            if self.env().caller() != self.env().account_id() {
+                return Err(MultisigError::CallerIsNotMultisig)
+            }
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and MultisigError otherwise
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `transaction_id` - Id of transaction
+        ///
+        /// # Description
+        /// Helper function to check if the transaction exists and was not executed yet.
+        fn check_pending(&self, transaction_id: TransactionId) -> Result<(), MultisigError> {
+            if self.transactions.get(&transaction_id).is_none() {
+                return Err(MultisigError::TransactionNotFound)
+            }
+            if self.is_executed(transaction_id) {
+                return Err(MultisigError::TransactionAlreadyExecuted)
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,560 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+/// This contract is the safety module insuring the protocol against shortfall.
+/// Users stake the protocol token or btokens and get shares of the module, which earn part of protocol revenue.
+/// The slasher (liquidity pool manager) can take up to max_slash_percentage of the stake to cover bad debt.
+/// Unstaking is possible only in the unstake window after the cooldown, so stakers can't leave right before a slash.
+#[brush::contract]
+pub mod safety_module {
+    /// imports of libraries and traits needed
+    use ink_prelude::string::String;
+    use ink_prelude::vec::Vec;
+    use ink_storage::Mapping;
+    use ink_storage::traits::SpreadAllocate;
+    use ink_lang::codegen::Env;
+    use brush::modifiers;
+    use brush::contracts::ownable::*;
+    use brush::contracts::psp22::*;
+    use brush::contracts::psp22::extensions::metadata::*;
+    use liquidity_pool_protocol::traits::safety_module::*;
+
+    /// precision of accumulated rewards per share
+    pub const REWARD_PRECISION: Balance = 1_000_000_000_000;
+
+    /// Defining storage and its components and also deriving needed imports to our storage.
+    #[ink(storage)]
+    #[derive(Default, SpreadAllocate, OwnableStorage, PSP22Storage, PSP22MetadataStorage)]
+    /// Our contracts variables are stored in this struct.
+    pub struct SafetyModuleContract {
+        #[OwnableStorageField]
+        ownable: OwnableData,
+        #[PSP22StorageField]
+        psp22: PSP22Data,
+        #[PSP22MetadataStorageField]
+        metadata: PSP22MetadataData,
+        /// the AccountId of the staked token
+        staked_token: AccountId,
+        /// the AccountId of the token rewards are paid in
+        reward_token: AccountId,
+        /// the AccountId allowed to slash the stake, the liquidity pool manager
+        slasher: AccountId,
+        /// Balance of staked tokens owned by the shares, it is lowered by slashing
+        total_staked: Balance,
+        /// time the staker waits after starting the cooldown
+        cooldown_period: Timestamp,
+        /// time after the cooldown in which the staker can unstake
+        unstake_window: Timestamp,
+        /// maximum percentage of the stake taken by one slash
+        max_slash_percentage: Balance,
+        /// rewards accumulated per share, multiplied by REWARD_PRECISION
+        reward_per_share: Balance,
+        /// mapping from account to reward_per_share when its rewards were last updated
+        paid_reward_per_share: Mapping<AccountId, Balance>,
+        /// mapping from account to rewards which were not claimed yet
+        rewards: Mapping<AccountId, Balance>,
+        /// mapping from account to Timestamp when its cooldown started
+        cooldown_starts: Mapping<AccountId, Timestamp>,
+    }
+
+    /// We inherit the implementation of the Ownable trait.
+    impl Ownable for SafetyModuleContract {}
+    /// We inherit the implementation of the PSP22 trait.
+    impl PSP22 for SafetyModuleContract {}
+    /// We inherit the implementation of the PSP22Metadata trait.
+    impl PSP22Metadata for SafetyModuleContract {}
+    /// We update rewards before every change of balances.
+    impl PSP22Transfer for SafetyModuleContract {
+        /// # Returns
+        /// Returns a Ok(()) with success and PSP22Error otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `from` AccountId shares are transferred from, None when minting
+        /// * `to` AccountId shares are transferred to, None when burning
+        /// * `amount` how many shares are transferred
+        ///
+        /// # Description
+        /// Hook called by PSP22 before every transfer, mint and burn, it accrues rewards of both sides with old balances.
+        /// Staking resets the cooldown of the staker. Transferred shares move the cooldown start of the receiver
+        /// towards the later cooldown start of the sender, weighted by the balances, so a dust transfer can't block the receiver.
+        fn _before_token_transfer(&mut self, from: Option<&AccountId>, to: Option<&AccountId>, amount: &Balance) -> Result<(), PSP22Error> {
+            if let Some(from) = from {
+                self.update_rewards(*from);
+            }
+            if let Some(to) = to {
+                self.update_rewards(*to);
+                match from {
+                    // newly staked shares would skip the cooldown, so the staker has to start it again
+                    None => self.cooldown_starts.remove(to),
+                    Some(from) => {
+                        if let Some(to_start) = self.cooldown_starts.get(to) {
+                            // shares without a running cooldown count as starting it now
+                            let now = self.env().block_timestamp();
+                            let from_start = match self.cooldown_starts.get(from) {
+                                Some(start) if now <= start + self.cooldown_period + self.unstake_window => start,
+                                _ => now,
+                            };
+                            if from_start > to_start && *amount > 0 {
+                                let to_balance = self.balance_of(*to);
+                                let weighted_start = (to_start as Balance * to_balance + from_start as Balance * amount) / (to_balance + amount);
+                                self.cooldown_starts.insert(to, &(weighted_start as Timestamp));
+                            }
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+    /// We implement functions declared in SafetyModule trait.
+    impl SafetyModule for SafetyModuleContract {
+        /// # Returns
+        /// Returns a Ok(()) with success and SafetyModuleError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `amount` - Balance of staked tokens
+        ///
+        /// # Description
+        /// stake transfers tokens from the caller and mints shares at the current exchange rate.
+        /// It resets the cooldown of the caller. Staking is not possible after the whole stake was slashed,
+        /// because the old shares would take the new stake.
+        #[ink(message)]
+        fn stake(&mut self, amount: Balance) -> Result<(), SafetyModuleError> {
+            let staker = self.env().caller();
+            let contract = self.env().account_id();
+            if amount == 0 {
+                return Err(SafetyModuleError::AmountNotSupported)
+            }
+            if PSP22Ref::allowance(&self.staked_token, staker, contract) < amount {
+                return Err(SafetyModuleError::InsufficientAllowance)
+            }
+            if PSP22Ref::balance_of(&self.staked_token, staker) < amount {
+                return Err(SafetyModuleError::InsufficientBalance)
+            }
+            // calculate shares, the first staker gets shares 1:1
+            let total_shares = self.total_supply();
+            if total_shares > 0 && self.total_staked == 0 {
+                return Err(SafetyModuleError::StakeFullySlashed)
+            }
+            let shares = if total_shares == 0 {
+                amount
+            } else {
+                amount * total_shares / self.total_staked
+            };
+            if shares == 0 {
+                return Err(SafetyModuleError::AmountNotSupported)
+            }
+            PSP22Ref::transfer_from_builder(&self.staked_token, staker, contract, amount, Vec::<u8>::new())
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            self.total_staked += amount;
+            self._mint(staker, shares)?;
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and SafetyModuleError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        ///
+        /// # Description
+        /// cooldown starts the cooldown of the caller, after it the caller can unstake during the unstake window.
+        #[ink(message)]
+        fn cooldown(&mut self) -> Result<(), SafetyModuleError> {
+            let staker = self.env().caller();
+            if self.balance_of(staker) == 0 {
+                return Err(SafetyModuleError::NothingStaked)
+            }
+            self.cooldown_starts.insert(&staker, &self.env().block_timestamp());
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Balance of returned staked tokens with success and SafetyModuleError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `share_amount` - Balance of shares to be burned
+        ///
+        /// # Description
+        /// unstake burns shares of the caller and returns staked tokens, it is possible only in the unstake window.
+        #[ink(message)]
+        fn unstake(&mut self, share_amount: Balance) -> Result<Balance, SafetyModuleError> {
+            let staker = self.env().caller();
+            if share_amount == 0 {
+                return Err(SafetyModuleError::AmountNotSupported)
+            }
+            if self.balance_of(staker) < share_amount {
+                return Err(SafetyModuleError::InsufficientBalance)
+            }
+            // check the cooldown of the staker
+            let cooldown_start = self.cooldown_starts.get(&staker).ok_or(SafetyModuleError::CooldownNotStarted)?;
+            let now = self.env().block_timestamp();
+            if now < cooldown_start + self.cooldown_period {
+                return Err(SafetyModuleError::CooldownNotFinished)
+            }
+            if now > cooldown_start + self.cooldown_period + self.unstake_window {
+                return Err(SafetyModuleError::UnstakeWindowFinished)
+            }
+            let amount = self.preview_unstake(share_amount);
+            self._burn_from(staker, share_amount)?;
+            self.total_staked -= amount;
+            // whole stake was withdrawn, next unstake needs a new cooldown
+            if self.balance_of(staker) == 0 {
+                self.cooldown_starts.remove(&staker);
+            }
+            PSP22Ref::transfer_builder(&self.staked_token, staker, amount, Vec::<u8>::new())
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            Ok(amount)
+        }
+
+        /// # Returns
+        /// Returns a Balance of claimed rewards with success and SafetyModuleError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        ///
+        /// # Description
+        /// claim_rewards transfers accrued rewards to the caller.
+        #[ink(message)]
+        fn claim_rewards(&mut self) -> Result<Balance, SafetyModuleError> {
+            let staker = self.env().caller();
+            self.update_rewards(staker);
+            let rewards = self.rewards.get(&staker).unwrap_or(0);
+            if rewards == 0 {
+                return Ok(0)
+            }
+            self.rewards.remove(&staker);
+            PSP22Ref::transfer_builder(&self.reward_token, staker, rewards, Vec::<u8>::new())
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            Ok(rewards)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and SafetyModuleError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `amount` - Balance of reward tokens
+        ///
+        /// # Description
+        /// distribute_rewards transfers reward tokens from the caller, e.g. the treasury with protocol revenue,
+        /// and splits them among current shares.
+        #[ink(message)]
+        fn distribute_rewards(&mut self, amount: Balance) -> Result<(), SafetyModuleError> {
+            let distributor = self.env().caller();
+            let total_shares = self.total_supply();
+            if total_shares == 0 {
+                return Err(SafetyModuleError::NothingStaked)
+            }
+            if amount == 0 {
+                return Err(SafetyModuleError::AmountNotSupported)
+            }
+            PSP22Ref::transfer_from_builder(&self.reward_token, distributor, self.env().account_id(), amount, Vec::<u8>::new())
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            self.reward_per_share += amount * REWARD_PRECISION / total_shares;
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Balance of slashed staked tokens with success and SafetyModuleError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `amount` - Balance of staked tokens needed to cover the shortfall
+        /// * `receiver` - AccountId which receives slashed tokens
+        ///
+        /// # Description
+        /// slash is slasher function taking staked tokens to cover bad debt.
+        /// It takes at most max_slash_percentage of the stake, shares stay so all stakers share the loss.
+        #[ink(message)]
+        fn slash(&mut self, amount: Balance, receiver: AccountId) -> Result<Balance, SafetyModuleError> {
+            if self.env().caller() != self.slasher {
+                return Err(SafetyModuleError::CallerIsNotSlasher)
+            }
+            let max_slash = self.total_staked * self.max_slash_percentage / 100;
+            let slashed = core::cmp::min(amount, max_slash);
+            if slashed == 0 {
+                return Ok(0)
+            }
+            self.total_staked -= slashed;
+            PSP22Ref::transfer_builder(&self.staked_token, receiver, slashed, Vec::<u8>::new())
+                .call_flags(ink_env::CallFlags::default().set_allow_reentry(true))
+                .fire()
+                .unwrap()?;
+            Ok(slashed)
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and SafetyModuleError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `slasher` - AccountId allowed to slash the stake
+        ///
+        /// # Description
+        /// set_slasher is owner function to change the slasher
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        fn set_slasher(&mut self, slasher: AccountId) -> Result<(), SafetyModuleError> {
+            self.slasher = slasher;
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and SafetyModuleError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `cooldown_period` - time the staker waits after starting the cooldown
+        ///
+        /// # Description
+        /// set_cooldown_period is owner function to change the cooldown period
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        fn set_cooldown_period(&mut self, cooldown_period: Timestamp) -> Result<(), SafetyModuleError> {
+            self.cooldown_period = cooldown_period;
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and SafetyModuleError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `unstake_window` - time after the cooldown in which the staker can unstake
+        ///
+        /// # Description
+        /// set_unstake_window is owner function to change the unstake window, it can't be zero
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        fn set_unstake_window(&mut self, unstake_window: Timestamp) -> Result<(), SafetyModuleError> {
+            if unstake_window == 0 {
+                return Err(SafetyModuleError::InvalidParameter)
+            }
+            self.unstake_window = unstake_window;
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns a Ok(()) with success and SafetyModuleError otherwise
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `max_slash_percentage` - maximum percentage of the stake taken by one slash
+        ///
+        /// # Description
+        /// set_max_slash_percentage is owner function to change how much of the stake can be slashed
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        fn set_max_slash_percentage(&mut self, max_slash_percentage: Balance) -> Result<(), SafetyModuleError> {
+            if max_slash_percentage > 100 {
+                return Err(SafetyModuleError::InvalidParameter)
+            }
+            self.max_slash_percentage = max_slash_percentage;
+            Ok(())
+        }
+
+        /// # Returns
+        /// Returns an AccountId
+        ///
+        /// # Description
+        /// get_staked_token returns the AccountId of the staked token
+        #[ink(message)]
+        fn get_staked_token(&self) -> AccountId {
+            self.staked_token
+        }
+
+        /// # Returns
+        /// Returns an AccountId
+        ///
+        /// # Description
+        /// get_reward_token returns the AccountId of the token rewards are paid in
+        #[ink(message)]
+        fn get_reward_token(&self) -> AccountId {
+            self.reward_token
+        }
+
+        /// # Returns
+        /// Returns an AccountId
+        ///
+        /// # Description
+        /// get_slasher returns the AccountId allowed to slash the stake
+        #[ink(message)]
+        fn get_slasher(&self) -> AccountId {
+            self.slasher
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Description
+        /// get_total_staked returns the Balance of staked tokens owned by the shares
+        #[ink(message)]
+        fn get_total_staked(&self) -> Balance {
+            self.total_staked
+        }
+
+        /// # Returns
+        /// Returns a Timestamp
+        ///
+        /// # Description
+        /// get_cooldown_period returns the time the staker waits after starting the cooldown
+        #[ink(message)]
+        fn get_cooldown_period(&self) -> Timestamp {
+            self.cooldown_period
+        }
+
+        /// # Returns
+        /// Returns a Timestamp
+        ///
+        /// # Description
+        /// get_unstake_window returns the time after the cooldown in which the staker can unstake
+        #[ink(message)]
+        fn get_unstake_window(&self) -> Timestamp {
+            self.unstake_window
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Description
+        /// get_max_slash_percentage returns the maximum percentage of the stake taken by one slash
+        #[ink(message)]
+        fn get_max_slash_percentage(&self) -> Balance {
+            self.max_slash_percentage
+        }
+
+        /// # Returns
+        /// Returns an optional Timestamp, None if the cooldown was not started
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `account` - AccountId of the staker
+        ///
+        /// # Description
+        /// get_cooldown_start returns when the cooldown of the staker started
+        #[ink(message)]
+        fn get_cooldown_start(&self, account: AccountId) -> Option<Timestamp> {
+            self.cooldown_starts.get(&account)
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `account` - AccountId of the staker
+        ///
+        /// # Description
+        /// get_claimable_rewards returns rewards of the staker including not yet updated ones
+        #[ink(message)]
+        fn get_claimable_rewards(&self, account: AccountId) -> Balance {
+            self.rewards.get(&account).unwrap_or(0) + self.pending_rewards(account)
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `share_amount` - Balance of shares
+        ///
+        /// # Description
+        /// preview_unstake returns the Balance of staked tokens the shares are worth
+        #[ink(message)]
+        fn preview_unstake(&self, share_amount: Balance) -> Balance {
+            let total_shares = self.total_supply();
+            if total_shares == 0 {
+                return 0
+            }
+            share_amount * self.total_staked / total_shares
+        }
+    }
+    /// Implementation of SafetyModule smart contract.
+    impl SafetyModuleContract {
+        /// # Returns
+        /// Returns Self
+        ///
+        /// # Arguments
+        /// * `staked_token` - AccountId of the protocol token or btoken to be staked
+        /// * `reward_token` - AccountId of the token rewards are paid in
+        /// * `slasher` - AccountId allowed to slash the stake, the liquidity pool manager
+        /// * `cooldown_period` - time the staker waits after starting the cooldown
+        /// * `unstake_window` - time after the cooldown in which the staker can unstake
+        /// * `max_slash_percentage` - maximum percentage of the stake taken by one slash
+        /// * `name` optional String for specifying our shares name in Metadata
+        /// * `symbol` optional String for specifying our shares symbol in Metadata
+        ///
+        /// # Description
+        /// Constructor for initializing our contract, the caller becomes the owner.
+        #[ink(constructor)]
+        pub fn new(
+            staked_token: AccountId,
+            reward_token: AccountId,
+            slasher: AccountId,
+            cooldown_period: Timestamp,
+            unstake_window: Timestamp,
+            max_slash_percentage: Balance,
+            name: Option<String>,
+            symbol: Option<String>,
+        ) -> Self {
+            assert!(unstake_window > 0 && max_slash_percentage <= 100);
+            ink_lang::codegen::initialize_contract(|instance: &mut SafetyModuleContract| {
+                instance._init_with_owner(instance.env().caller());
+                instance.staked_token = staked_token;
+                instance.reward_token = reward_token;
+                instance.slasher = slasher;
+                instance.cooldown_period = cooldown_period;
+                instance.unstake_window = unstake_window;
+                instance.max_slash_percentage = max_slash_percentage;
+                // Set metadata variables.
+                instance.metadata.name = name;
+                instance.metadata.symbol = symbol;
+                instance.metadata.decimals = PSP22MetadataRef::token_decimals(&staked_token);
+            })
+        }
+
+        /// # Returns
+        /// Returns a Balance
+        ///
+        /// # Arguments
+        /// * `&self` - used so we can read variables of self component
+        /// * `account` - AccountId of the staker
+        ///
+        /// # Description
+        /// Helper function returning rewards accrued since the last update of the staker.
+        fn pending_rewards(&self, account: AccountId) -> Balance {
+            let paid = self.paid_reward_per_share.get(&account).unwrap_or(0);
+            self.balance_of(account) * (self.reward_per_share - paid) / REWARD_PRECISION
+        }
+
+        /// # Returns
+        /// Without a return value
+        ///
+        /// # Arguments
+        /// * `&mut self` - used so we can mutate variables of self component
+        /// * `account` - AccountId of the staker
+        ///
+        /// # Description
+        /// Helper function moving accrued rewards of the staker to its rewards, it has to be called before balance changes.
+        fn update_rewards(&mut self, account: AccountId) {
+            let pending = self.pending_rewards(account);
+            if pending > 0 {
+                let rewards = self.rewards.get(&account).unwrap_or(0);
+                self.rewards.insert(&account, &(rewards + pending));
+            }
+            self.paid_reward_per_share.insert(&account, &self.reward_per_share);
+        }
+    }
+}
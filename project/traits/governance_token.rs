@@ -0,0 +1,20 @@
+use brush::traits::AccountId;
+use brush::traits::Balance;
+use brush::traits::BlockNumber;
+use brush::contracts::traits::psp22::*;
+use brush::contracts::traits::psp22::extensions::metadata::*;
+
+#[brush::wrapper]
+pub type GovernanceTokenRef = dyn GovernanceToken + PSP22 + PSP22Metadata;
+
+#[brush::trait_definition]
+pub trait GovernanceToken: PSP22 + PSP22Metadata{
+    #[ink(message)]
+    fn get_votes(&self, account: AccountId) -> Balance;
+
+    #[ink(message)]
+    fn get_past_votes(&self, account: AccountId, block_number: BlockNumber) -> Result<Balance, PSP22Error>;
+
+    #[ink(message)]
+    fn get_past_total_supply(&self, block_number: BlockNumber) -> Result<Balance, PSP22Error>;
+}
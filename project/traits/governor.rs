@@ -0,0 +1,149 @@
+use ink_prelude::vec::Vec;
+use ink_prelude::string::String;
+use ink_storage::traits::PackedLayout;
+use ink_storage::traits::SpreadLayout;
+use brush::traits::AccountId;
+use brush::traits::Balance;
+use brush::traits::BlockNumber;
+use brush::traits::Timestamp;
+use brush::contracts::traits::access_control::*;
+use brush::contracts::traits::psp22::*;
+use crate::traits::transaction::Transaction;
+
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+
+pub type ProposalId = u32;
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout, scale_info::TypeInfo))]
+pub struct Proposal{
+    pub proposer: AccountId,
+    pub transactions: Vec<Transaction>,
+    pub description: String,
+    pub vote_start: BlockNumber,
+    pub vote_end: BlockNumber,
+    pub for_votes: Balance,
+    pub against_votes: Balance,
+    pub abstain_votes: Balance,
+    pub eta: Option<Timestamp>,
+    pub executed: bool,
+    pub canceled: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum ProposalState {
+    Pending,
+    Active,
+    Canceled,
+    Defeated,
+    Succeeded,
+    Queued,
+    Executed
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum VoteType {
+    Against,
+    For,
+    Abstain
+}
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum GovernorError {
+    AccessControlError(AccessControlError),
+    PSP22Error(PSP22Error),
+    ProposalNotFound,
+    EmptyProposal,
+    BelowProposalThreshold,
+    ProposalNotActive,
+    ProposalNotSucceeded,
+    ProposalNotQueued,
+    ProposalAlreadyFinished,
+    AlreadyVoted,
+    NotTheProposer,
+    TimelockNotReady,
+    InvalidParameter,
+    TransactionFailed
+}
+
+#[brush::wrapper]
+pub type GovernorRef = dyn Governor + AccessControl;
+
+#[brush::trait_definition]
+pub trait Governor: AccessControl{
+    #[ink(message)]
+    fn propose(&mut self, transactions: Vec<Transaction>, description: String) -> Result<ProposalId, GovernorError>;
+
+    #[ink(message)]
+    fn cast_vote(&mut self, proposal_id: ProposalId, vote: VoteType) -> Result<Balance, GovernorError>;
+
+    #[ink(message)]
+    fn queue(&mut self, proposal_id: ProposalId) -> Result<(), GovernorError>;
+
+    #[ink(message, payable)]
+    fn execute(&mut self, proposal_id: ProposalId) -> Result<(), GovernorError>;
+
+    #[ink(message)]
+    fn cancel(&mut self, proposal_id: ProposalId) -> Result<(), GovernorError>;
+
+    #[ink(message)]
+    fn get_proposal_state(&self, proposal_id: ProposalId) -> Result<ProposalState, GovernorError>;
+
+    #[ink(message)]
+    fn get_proposal(&self, proposal_id: ProposalId) -> Option<Proposal>;
+
+    #[ink(message)]
+    fn has_voted(&self, proposal_id: ProposalId, account: AccountId) -> bool;
+
+    #[ink(message)]
+    fn get_quorum(&self, block_number: BlockNumber) -> Result<Balance, GovernorError>;
+
+    #[ink(message)]
+    fn get_governance_token(&self) -> AccountId;
+
+    #[ink(message)]
+    fn set_voting_delay(&mut self, voting_delay: BlockNumber) -> Result<(), GovernorError>;
+
+    #[ink(message)]
+    fn set_voting_period(&mut self, voting_period: BlockNumber) -> Result<(), GovernorError>;
+
+    #[ink(message)]
+    fn set_quorum_numerator(&mut self, quorum_numerator: Balance) -> Result<(), GovernorError>;
+
+    #[ink(message)]
+    fn set_proposal_threshold(&mut self, proposal_threshold: Balance) -> Result<(), GovernorError>;
+
+    #[ink(message)]
+    fn set_timelock_delay(&mut self, timelock_delay: Timestamp) -> Result<(), GovernorError>;
+
+    #[ink(message)]
+    fn get_voting_delay(&self) -> BlockNumber;
+
+    #[ink(message)]
+    fn get_voting_period(&self) -> BlockNumber;
+
+    #[ink(message)]
+    fn get_quorum_numerator(&self) -> Balance;
+
+    #[ink(message)]
+    fn get_proposal_threshold(&self) -> Balance;
+
+    #[ink(message)]
+    fn get_timelock_delay(&self) -> Timestamp;
+}
+
+impl From<AccessControlError> for GovernorError {
+    fn from(access: AccessControlError) -> Self {
+        GovernorError::AccessControlError(access)
+    }
+}
+
+impl From<PSP22Error> for GovernorError {
+    fn from(error: PSP22Error) -> Self {
+        GovernorError::PSP22Error(error)
+    }
+}
@@ -1,9 +1,150 @@
+use ink_prelude::vec::Vec;
+use ink_storage::traits::PackedLayout;
+use ink_storage::traits::SpreadLayout;
 use brush::traits::AccountId;
 use brush::traits::Balance;
+use brush::traits::Timestamp;
 use brush::contracts::traits::access_control::*;
+use brush::contracts::traits::access_control::extensions::enumerable::*;
+use brush::contracts::traits::ownable::*;
 use brush::contracts::traits::pausable::*;
 use brush::contracts::traits::psp22::*;
 use brush::contracts::traits::psp34::*;
+use crate::traits::safety_module::SafetyModuleError;
+use crate::traits::swap_venue::SwapError;
+
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+
+pub type CategoryId = u8;
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout, scale_info::TypeInfo))]
+pub struct AssetCategory{
+    pub ltv: Balance,
+    pub liquidation_threshold: Balance,
+    pub liquidation_bonus: Balance,
+    pub price_source: Option<AccountId>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout, scale_info::TypeInfo))]
+pub enum MarketState {
+    Active,
+    Frozen,
+    Paused,
+    Delisted
+}
+
+impl Default for MarketState {
+    fn default() -> Self {
+        MarketState::Active
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout, scale_info::TypeInfo))]
+pub enum ParameterKind {
+    ConversionRate,
+    SupplyCap,
+    BorrowCap,
+    DebtCeiling,
+    LoanToValue,
+    LiquidationThreshold,
+    LiquidationBonus,
+    AuctionStartPremium,
+    AuctionFloor,
+    AuctionDuration,
+    MaxLiquidationBonus,
+    DeleverageFee,
+    MaxPriceDeviation,
+    MaxBlockOutflow,
+    RateLimitCapacity,
+    RateLimitRefillPeriod,
+    TermRate,
+    GracePeriod,
+    RefinanceSlippage
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout, scale_info::TypeInfo))]
+pub struct ParameterBounds{
+    pub min: Balance,
+    pub max: Balance,
+    pub max_change: Balance,
+    pub window: Timestamp,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout, scale_info::TypeInfo))]
+pub struct CircuitBreakerConfig{
+    pub max_price_deviation: Balance,
+    pub price_window: Timestamp,
+    pub max_block_outflow: Balance,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout, scale_info::TypeInfo))]
+pub struct RateLimitConfig{
+    pub capacity: Balance,
+    pub refill_period: Timestamp,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout, scale_info::TypeInfo))]
+pub struct BadDebt{
+    pub total_shortfall: Balance,
+    pub covered_by_reserves: Balance,
+    pub covered_by_safety_module: Balance,
+    pub written_down: Balance,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout, scale_info::TypeInfo))]
+pub struct AuctionConfig{
+    pub start_premium: Balance,
+    pub floor: Balance,
+    pub duration: Timestamp,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout, scale_info::TypeInfo))]
+pub struct Auction{
+    pub borrower: AccountId,
+    pub collateral_token: AccountId,
+    pub borrow_token: AccountId,
+    pub collateral_amount: Balance,
+    pub debt: Balance,
+    pub principal: Balance,
+    pub initial_collateral: Balance,
+    pub start_value: Balance,
+    pub start_time: Timestamp,
+    pub config: AuctionConfig,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout, scale_info::TypeInfo))]
+pub struct LiquidationBonusCurve{
+    pub min_bonus: Balance,
+    pub max_bonus: Balance,
+    pub max_bonus_health: Balance,
+}
+
+pub type WithdrawalRequestId = u32;
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout, scale_info::TypeInfo))]
+pub struct WithdrawalRequest{
+    pub owner: AccountId,
+    pub btoken_amount: Balance,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout, scale_info::TypeInfo))]
+pub enum CircuitBreakerReason {
+    PriceDeviation,
+    Outflow
+}
 
 #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
@@ -11,7 +152,10 @@ pub enum PoolManagerError {
     PSP22Error(PSP22Error),
     PSP34Error(PSP34Error),
     AccessControlError(AccessControlError),
+    OwnableError(OwnableError),
     PausableError(PausableError),
+    SafetyModuleError(SafetyModuleError),
+    SwapError(SwapError),
     AssetNotFound,
     BTokenNotFound,
     AssetAlreadySupported,
@@ -26,29 +170,188 @@ pub enum PoolManagerError {
     AmountNotSupported,
     NotTheLoanOwner,
     LoanAlreadyLiquidated,
-    LoanUnliquidable
+    LoanUnliquidable,
+    SupplyCapExceeded,
+    BorrowCapExceeded,
+    AssetNotBorrowableInIsolation,
+    DebtCeilingExceeded,
+    CategoryNotFound,
+    InvalidCategory,
+    AssetNotInCategory,
+    MarketFrozen,
+    MarketPaused,
+    MarketDelisted,
+    InvalidParameterBounds,
+    ParameterOutOfBounds,
+    ParameterChangeTooLarge,
+    CircuitBreakerNotTripped,
+    RateLimitExceeded,
+    WithdrawalRequestNotFound,
+    NotTheRequestOwner,
+    WithdrawalQueueNotEmpty,
+    InsufficientProtocolReserves,
+    InvalidSafetyModule,
+    ProtocolShutdown,
+    ProtocolNotShutdown,
+    SettlementNotStarted,
+    InvalidAuctionConfig,
+    AuctionNotFound,
+    AuctionActive,
+    AuctionNotFinished,
+    InvalidBonusCurve,
+    InvalidDeleverageFee,
+    InsufficientCollateral,
+    SwapVenueNotFound,
+    TermNotSupported,
+    LoanExpired,
+    FixedTermLoan,
+    InvalidSlippage,
+    InvalidInterestRate,
+    InvalidGracePeriod,
+    OpenEndedLoan
+}
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct LiquidationResult{
+    pub liquidation: Result<(), PoolManagerError>,
+    pub purchase: Option<Result<Balance, PoolManagerError>>,
 }
 
 #[brush::wrapper]
-pub type PoolManagerRef = dyn PoolManager + AccessControl + Pausable;
+pub type PoolManagerRef = dyn PoolManager + AccessControl + AccessControlEnumerable + Pausable;
 
 #[brush::trait_definition]
-pub trait PoolManager: AccessControl + Pausable{
+pub trait PoolManager: AccessControl + AccessControlEnumerable + Pausable{
     #[ink(message, payable)]
     fn lend(&mut self, asset_address: AccountId, amount: Balance) -> Result<(), PoolManagerError>;
 
     #[ink(message)]
     fn withdraw(&mut self, btoken_address: AccountId, btoken_amount: Balance) -> Result<(), PoolManagerError> ;
 
+    #[ink(message)]
+    fn request_withdrawal(&mut self, btoken_address: AccountId, btoken_amount: Balance) -> Result<WithdrawalRequestId, PoolManagerError>;
+
+    #[ink(message)]
+    fn cancel_withdrawal_request(&mut self, asset_address: AccountId, request_id: WithdrawalRequestId) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn process_queue(&mut self, asset_address: AccountId, max_requests: u32) -> Result<Balance, PoolManagerError>;
+
+    #[ink(message)]
+    fn get_withdrawal_request(&mut self, asset_address: AccountId, request_id: WithdrawalRequestId) -> Option<WithdrawalRequest>;
+
+    #[ink(message)]
+    fn get_parked_withdrawal_request(&mut self, asset_address: AccountId, request_id: WithdrawalRequestId) -> Option<WithdrawalRequest>;
+
+    #[ink(message)]
+    fn get_queue_length(&mut self, asset_address: AccountId) -> u32;
+
+    #[ink(message)]
+    fn get_queue_position(&mut self, asset_address: AccountId, request_id: WithdrawalRequestId) -> Option<u32>;
+
+    #[ink(message)]
+    fn get_pending_withdrawal(&mut self, asset_address: AccountId, user: AccountId) -> Balance;
+
     #[ink(message)]
     fn borrow(&mut self, asset_address: AccountId, collateral_address: AccountId, amount: Balance) -> Result<(), PoolManagerError>;
 
+    #[ink(message)]
+    fn borrow_with_term(&mut self, asset_address: AccountId, collateral_address: AccountId, amount: Balance, term: Timestamp) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn roll_over(&mut self, loan_id: Id, term: Timestamp) -> Result<Balance, PoolManagerError>;
+
+    #[ink(message)]
+    fn get_loans_approaching_maturity(&mut self, offset: u32, limit: u32, within: Timestamp) -> Vec<Id>;
+
+    #[ink(message)]
+    fn set_term_rate(&mut self, term: Timestamp, interest_rate: Option<Balance>) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn get_term_rate(&mut self, term: Timestamp) -> Option<Balance>;
+
+    #[ink(message)]
+    fn set_grace_period(&mut self, grace_period: Timestamp) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn get_grace_period(&mut self) -> Timestamp;
+
     #[ink(message)]
     fn repay(&mut self, loan_id: Id, repay_amount: Balance) -> Result<bool, PoolManagerError>;
 
+    #[ink(message)]
+    fn repay_with_collateral(&mut self, loan_id: Id, collateral_amount: Balance) -> Result<Balance, PoolManagerError>;
+
+    #[ink(message)]
+    fn swap_collateral(&mut self, loan_id: Id, new_collateral: AccountId, amount: Balance) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn refinance(&mut self, loan_id: Id, new_borrow_asset: AccountId) -> Result<Balance, PoolManagerError>;
+
+    #[ink(message)]
+    fn set_refinance_slippage(&mut self, sold_asset: AccountId, bought_asset: AccountId, slippage: Balance) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn get_refinance_slippage(&mut self, sold_asset: AccountId, bought_asset: AccountId) -> Balance;
+
+    #[ink(message)]
+    fn set_deleverage_fee(&mut self, collateral_address: AccountId, fee: Balance) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn get_deleverage_fee(&mut self, collateral_address: AccountId) -> Balance;
+
+    #[ink(message)]
+    fn set_swap_venue(&mut self, collateral_address: AccountId, asset_address: AccountId, swap_venue: Option<AccountId>) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn get_swap_venue(&mut self, collateral_address: AccountId, asset_address: AccountId) -> Option<AccountId>;
+
     #[ink(message)]
     fn liquidate_loan(&mut self, loan_id: Id) -> Result<(), PoolManagerError>;
 
+    #[ink(message)]
+    fn liquidate_batch(&mut self, liquidations: Vec<(Id, Balance)>) -> Vec<LiquidationResult>;
+
+    #[ink(message)]
+    fn find_liquidatable(&mut self, offset: u32, limit: u32) -> Vec<Id>;
+
+    #[ink(message)]
+    fn get_loan_health(&mut self, loan_id: Id) -> Result<Balance, PoolManagerError>;
+
+    #[ink(message)]
+    fn get_liquidation_bonus(&mut self, loan_id: Id) -> Result<Balance, PoolManagerError>;
+
+    #[ink(message)]
+    fn set_liquidation_bonus_curve(&mut self, collateral_address: AccountId, curve: Option<LiquidationBonusCurve>) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn get_liquidation_bonus_curve(&mut self, collateral_address: AccountId) -> Option<LiquidationBonusCurve>;
+
+    #[ink(message)]
+    fn bid(&mut self, loan_id: Id, collateral_amount: Balance) -> Result<Balance, PoolManagerError>;
+
+    #[ink(message)]
+    fn close_auction(&mut self, loan_id: Id) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn buy_pool_collateral(&mut self, asset_address: AccountId, collateral_address: AccountId, collateral_amount: Balance) -> Result<Balance, PoolManagerError>;
+
+    #[ink(message)]
+    fn get_auction(&mut self, loan_id: Id) -> Option<Auction>;
+
+    #[ink(message)]
+    fn get_auction_price(&mut self, loan_id: Id, collateral_amount: Balance) -> Result<Balance, PoolManagerError>;
+
+    #[ink(message)]
+    fn get_active_auctions(&mut self) -> Vec<Id>;
+
+    #[ink(message)]
+    fn set_auction_config(&mut self, collateral_address: AccountId, config: Option<AuctionConfig>) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn get_auction_config(&mut self, collateral_address: AccountId) -> AuctionConfig;
+
     #[ink(message)]
     fn get_asset_acceptance(&mut self, asset_address: AccountId) -> bool;
 
@@ -84,6 +387,162 @@ pub trait PoolManager: AccessControl + Pausable{
 
     #[ink(message)]
     fn get_btoken_from_asset(&mut self, asset_address: AccountId) -> Result<AccountId, PoolManagerError>;
+
+    #[ink(message)]
+    fn set_supply_cap(&mut self, asset_address: AccountId, supply_cap: Option<Balance>) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn set_borrow_cap(&mut self, asset_address: AccountId, borrow_cap: Option<Balance>) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn get_supply_cap(&mut self, asset_address: AccountId) -> Option<Balance>;
+
+    #[ink(message)]
+    fn get_borrow_cap(&mut self, asset_address: AccountId) -> Option<Balance>;
+
+    #[ink(message)]
+    fn get_remaining_supply_capacity(&mut self, asset_address: AccountId) -> Result<Option<Balance>, PoolManagerError>;
+
+    #[ink(message)]
+    fn get_remaining_borrow_capacity(&mut self, asset_address: AccountId) -> Result<Option<Balance>, PoolManagerError>;
+
+    #[ink(message)]
+    fn set_collateral_isolation(&mut self, collateral_address: AccountId, isolated: bool) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn set_isolated_borrowable(&mut self, collateral_address: AccountId, asset_address: AccountId, debt_ceiling: Option<Balance>) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn is_collateral_isolated(&mut self, collateral_address: AccountId) -> bool;
+
+    #[ink(message)]
+    fn get_debt_ceiling(&mut self, collateral_address: AccountId, asset_address: AccountId) -> Option<Balance>;
+
+    #[ink(message)]
+    fn get_isolated_borrowable(&mut self, collateral_address: AccountId, asset_address: AccountId) -> bool;
+
+    #[ink(message)]
+    fn get_collateral_debt(&mut self, collateral_address: AccountId, asset_address: AccountId) -> Balance;
+
+    #[ink(message)]
+    fn set_category(&mut self, category_id: CategoryId, category: AssetCategory) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn set_asset_category(&mut self, asset_address: AccountId, category_id: CategoryId) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn set_user_category(&mut self, category_id: CategoryId) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn get_category(&mut self, category_id: CategoryId) -> Option<AssetCategory>;
+
+    #[ink(message)]
+    fn get_asset_category(&mut self, asset_address: AccountId) -> CategoryId;
+
+    #[ink(message)]
+    fn get_user_category(&mut self, user: AccountId) -> CategoryId;
+
+    #[ink(message)]
+    fn set_market_state(&mut self, asset_address: AccountId, state: MarketState) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn get_market_state(&mut self, asset_address: AccountId) -> MarketState;
+
+    #[ink(message)]
+    fn pause(&mut self) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn unpause(&mut self) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn pause_operations(&mut self, operations: OperationMask, asset_address: Option<AccountId>) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn unpause_operations(&mut self, operations: OperationMask, asset_address: Option<AccountId>) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn set_parameter_bounds(&mut self, kind: ParameterKind, bounds: Option<ParameterBounds>) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn get_parameter_bounds(&mut self, kind: ParameterKind) -> Option<ParameterBounds>;
+
+    #[ink(message)]
+    fn set_circuit_breaker(&mut self, asset_address: AccountId, config: Option<CircuitBreakerConfig>) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn get_circuit_breaker(&mut self, asset_address: AccountId) -> Option<CircuitBreakerConfig>;
+
+    #[ink(message)]
+    fn is_circuit_breaker_tripped(&mut self, asset_address: AccountId) -> bool;
+
+    #[ink(message)]
+    fn clear_circuit_breaker(&mut self, asset_address: AccountId) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn set_rate_limit(&mut self, asset_address: AccountId, config: Option<RateLimitConfig>) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn get_rate_limit(&mut self, asset_address: AccountId) -> Option<RateLimitConfig>;
+
+    #[ink(message)]
+    fn get_remaining_outflow_capacity(&mut self, asset_address: AccountId) -> Option<Balance>;
+
+    #[ink(message)]
+    fn deposit_protocol_reserves(&mut self, asset_address: AccountId, amount: Balance) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn withdraw_protocol_reserves(&mut self, asset_address: AccountId, amount: Balance, to: AccountId) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn get_protocol_reserves(&mut self, asset_address: AccountId) -> Balance;
+
+    #[ink(message)]
+    fn set_safety_module(&mut self, asset_address: AccountId, safety_module: Option<AccountId>) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn get_safety_module(&mut self, asset_address: AccountId) -> Option<AccountId>;
+
+    #[ink(message)]
+    fn get_bad_debt(&mut self, asset_address: AccountId) -> BadDebt;
+
+    #[ink(message)]
+    fn get_loan_shortfall(&mut self, loan_id: Id) -> Result<Balance, PoolManagerError>;
+
+    #[ink(message)]
+    fn shutdown(&mut self) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn is_shutdown(&mut self) -> bool;
+
+    #[ink(message)]
+    fn get_shutdown_timestamp(&mut self) -> Option<Timestamp>;
+
+    #[ink(message)]
+    fn settle_loan(&mut self, loan_id: Id) -> Result<Balance, PoolManagerError>;
+
+    #[ink(message)]
+    fn redeem_settlement(&mut self, btoken_address: AccountId, btoken_amount: Balance) -> Result<Vec<(AccountId, Balance)>, PoolManagerError>;
+
+    #[ink(message)]
+    fn get_settlement_share(&mut self, btoken_address: AccountId, btoken_amount: Balance) -> Result<Vec<(AccountId, Balance)>, PoolManagerError>;
+
+    #[ink(message)]
+    fn get_pool_collateral(&mut self, asset_address: AccountId, collateral_address: AccountId) -> Balance;
+
+    #[ink(message)]
+    fn get_pool_collateral_tokens(&mut self, asset_address: AccountId) -> Vec<AccountId>;
+
+    #[ink(message)]
+    fn get_loan_debt(&mut self, loan_id: Id) -> Result<Balance, PoolManagerError>;
+
+    #[ink(message)]
+    fn transfer_contract_ownership(&mut self, contract_address: AccountId, new_owner: AccountId) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn accept_contract_ownership(&mut self, contract_address: AccountId) -> Result<(), PoolManagerError>;
+
+    #[ink(message)]
+    fn cancel_contract_ownership_transfer(&mut self, contract_address: AccountId) -> Result<(), PoolManagerError>;
 }
 
 impl From<AccessControlError> for PoolManagerError {
@@ -92,12 +551,30 @@ impl From<AccessControlError> for PoolManagerError {
     }
 }
 
+impl From<OwnableError> for PoolManagerError {
+    fn from(ownable: OwnableError) -> Self {
+        PoolManagerError::OwnableError(ownable)
+    }
+}
+
 impl From<PausableError> for PoolManagerError {
     fn from(access: PausableError) -> Self {
         PoolManagerError::PausableError(access)
     }
 }
 
+impl From<SafetyModuleError> for PoolManagerError {
+    fn from(error: SafetyModuleError) -> Self {
+        PoolManagerError::SafetyModuleError(error)
+    }
+}
+
+impl From<SwapError> for PoolManagerError {
+    fn from(error: SwapError) -> Self {
+        PoolManagerError::SwapError(error)
+    }
+}
+
 impl From<PSP22Error> for PoolManagerError {
     fn from(error: PSP22Error) -> Self {
         PoolManagerError::PSP22Error(error)
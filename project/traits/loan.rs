@@ -1,3 +1,4 @@
+use ink_prelude::vec::Vec;
 use ink_storage::traits::PackedLayout;
 use ink_storage::traits::SpreadLayout;
 use brush::traits::AccountId;
@@ -6,6 +7,7 @@ use brush::traits::Timestamp;
 use brush::contracts::traits::ownable::*;
 use brush::contracts::traits::psp34::*;
 use brush::contracts::traits::psp34::extensions::metadata::*;
+use crate::traits::liquidity_pool_manager::CategoryId;
 
 #[cfg(feature = "std")]
 use ink_storage::traits::StorageLayout;
@@ -21,6 +23,9 @@ pub struct LoanInfo{
     pub liquidation_price: Balance,
     pub timestamp: Timestamp,
     pub already_liquidated: bool,
+    pub category: CategoryId,
+    pub maturity: Option<Timestamp>,
+    pub interest_rate: Balance,
 }
 
 #[brush::wrapper]
@@ -40,6 +45,12 @@ pub trait Loan: Ownable + PSP34 + PSP34Metadata{
     #[ink(message)]
     fn liquidate_loan(&mut self, loan_id: Id) -> Result<(), PSP34Error>;
 
+    #[ink(message)]
+    fn set_loan_info(&mut self, loan_id: Id, loan_info: LoanInfo) -> Result<(), PSP34Error>;
+
     #[ink(message)]
     fn get_loan_info(&self, loan_id: Id) -> Result<LoanInfo, PSP34Error>;
+
+    #[ink(message)]
+    fn get_loan_list(&self, offset: u32, limit: u32) -> Vec<(Id, LoanInfo)>;
 }
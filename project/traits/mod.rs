@@ -1,4 +1,10 @@
 pub mod btoken;
+pub mod governance_token;
+pub mod governor;
 pub mod liquidity_pool_manager;
 pub mod loan;
+pub mod multisig;
+pub mod safety_module;
 pub mod stablecoin;
+pub mod swap_venue;
+pub mod transaction;
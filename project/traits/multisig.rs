@@ -0,0 +1,69 @@
+use ink_prelude::vec::Vec;
+use brush::traits::AccountId;
+use crate::traits::transaction::Transaction;
+
+pub type TransactionId = u32;
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum MultisigError {
+    NotAnOwner,
+    CallerIsNotMultisig,
+    OwnerAlreadyExists,
+    OwnerNotFound,
+    InvalidThreshold,
+    TransactionNotFound,
+    TransactionAlreadyExecuted,
+    AlreadyConfirmed,
+    NotConfirmed,
+    ThresholdNotReached,
+    TransactionFailed
+}
+
+#[brush::wrapper]
+pub type MultisigRef = dyn Multisig;
+
+#[brush::trait_definition]
+pub trait Multisig{
+    #[ink(message)]
+    fn submit_transaction(&mut self, transaction: Transaction) -> Result<TransactionId, MultisigError>;
+
+    #[ink(message)]
+    fn confirm_transaction(&mut self, transaction_id: TransactionId) -> Result<(), MultisigError>;
+
+    #[ink(message)]
+    fn revoke_confirmation(&mut self, transaction_id: TransactionId) -> Result<(), MultisigError>;
+
+    #[ink(message, payable)]
+    fn execute_transaction(&mut self, transaction_id: TransactionId) -> Result<(), MultisigError>;
+
+    #[ink(message)]
+    fn add_owner(&mut self, owner: AccountId) -> Result<(), MultisigError>;
+
+    #[ink(message)]
+    fn remove_owner(&mut self, owner: AccountId) -> Result<(), MultisigError>;
+
+    #[ink(message)]
+    fn change_threshold(&mut self, threshold: u32) -> Result<(), MultisigError>;
+
+    #[ink(message)]
+    fn get_owners(&self) -> Vec<AccountId>;
+
+    #[ink(message)]
+    fn get_threshold(&self) -> u32;
+
+    #[ink(message)]
+    fn is_owner(&self, account: AccountId) -> bool;
+
+    #[ink(message)]
+    fn get_transaction(&self, transaction_id: TransactionId) -> Option<Transaction>;
+
+    #[ink(message)]
+    fn get_confirmation_count(&self, transaction_id: TransactionId) -> u32;
+
+    #[ink(message)]
+    fn is_confirmed_by(&self, transaction_id: TransactionId, owner: AccountId) -> bool;
+
+    #[ink(message)]
+    fn is_executed(&self, transaction_id: TransactionId) -> bool;
+}
@@ -0,0 +1,101 @@
+use brush::traits::AccountId;
+use brush::traits::Balance;
+use brush::traits::Timestamp;
+use brush::contracts::traits::ownable::*;
+use brush::contracts::traits::psp22::*;
+use brush::contracts::traits::psp22::extensions::metadata::*;
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum SafetyModuleError {
+    PSP22Error(PSP22Error),
+    OwnableError(OwnableError),
+    AmountNotSupported,
+    InsufficientAllowance,
+    InsufficientBalance,
+    NothingStaked,
+    CooldownNotStarted,
+    CooldownNotFinished,
+    UnstakeWindowFinished,
+    CallerIsNotSlasher,
+    StakeFullySlashed,
+    InvalidParameter
+}
+
+impl From<PSP22Error> for SafetyModuleError {
+    fn from(error: PSP22Error) -> Self {
+        SafetyModuleError::PSP22Error(error)
+    }
+}
+
+impl From<OwnableError> for SafetyModuleError {
+    fn from(error: OwnableError) -> Self {
+        SafetyModuleError::OwnableError(error)
+    }
+}
+
+#[brush::wrapper]
+pub type SafetyModuleRef = dyn SafetyModule + PSP22 + PSP22Metadata;
+
+#[brush::trait_definition]
+pub trait SafetyModule: PSP22 + PSP22Metadata{
+    #[ink(message)]
+    fn stake(&mut self, amount: Balance) -> Result<(), SafetyModuleError>;
+
+    #[ink(message)]
+    fn cooldown(&mut self) -> Result<(), SafetyModuleError>;
+
+    #[ink(message)]
+    fn unstake(&mut self, share_amount: Balance) -> Result<Balance, SafetyModuleError>;
+
+    #[ink(message)]
+    fn claim_rewards(&mut self) -> Result<Balance, SafetyModuleError>;
+
+    #[ink(message)]
+    fn distribute_rewards(&mut self, amount: Balance) -> Result<(), SafetyModuleError>;
+
+    #[ink(message)]
+    fn slash(&mut self, amount: Balance, receiver: AccountId) -> Result<Balance, SafetyModuleError>;
+
+    #[ink(message)]
+    fn set_slasher(&mut self, slasher: AccountId) -> Result<(), SafetyModuleError>;
+
+    #[ink(message)]
+    fn set_cooldown_period(&mut self, cooldown_period: Timestamp) -> Result<(), SafetyModuleError>;
+
+    #[ink(message)]
+    fn set_unstake_window(&mut self, unstake_window: Timestamp) -> Result<(), SafetyModuleError>;
+
+    #[ink(message)]
+    fn set_max_slash_percentage(&mut self, max_slash_percentage: Balance) -> Result<(), SafetyModuleError>;
+
+    #[ink(message)]
+    fn get_staked_token(&self) -> AccountId;
+
+    #[ink(message)]
+    fn get_reward_token(&self) -> AccountId;
+
+    #[ink(message)]
+    fn get_slasher(&self) -> AccountId;
+
+    #[ink(message)]
+    fn get_total_staked(&self) -> Balance;
+
+    #[ink(message)]
+    fn get_cooldown_period(&self) -> Timestamp;
+
+    #[ink(message)]
+    fn get_unstake_window(&self) -> Timestamp;
+
+    #[ink(message)]
+    fn get_max_slash_percentage(&self) -> Balance;
+
+    #[ink(message)]
+    fn get_cooldown_start(&self, account: AccountId) -> Option<Timestamp>;
+
+    #[ink(message)]
+    fn get_claimable_rewards(&self, account: AccountId) -> Balance;
+
+    #[ink(message)]
+    fn preview_unstake(&self, share_amount: Balance) -> Balance;
+}
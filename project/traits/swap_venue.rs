@@ -0,0 +1,30 @@
+use brush::traits::AccountId;
+use brush::traits::Balance;
+use brush::contracts::traits::psp22::*;
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum SwapError {
+    PSP22Error(PSP22Error),
+    PairNotSupported,
+    InsufficientLiquidity,
+    InsufficientOutputAmount
+}
+
+impl From<PSP22Error> for SwapError {
+    fn from(error: PSP22Error) -> Self {
+        SwapError::PSP22Error(error)
+    }
+}
+
+#[brush::wrapper]
+pub type SwapVenueRef = dyn SwapVenue;
+
+#[brush::trait_definition]
+pub trait SwapVenue{
+    #[ink(message)]
+    fn swap(&mut self, token_in: AccountId, token_out: AccountId, amount_in: Balance, min_amount_out: Balance) -> Result<Balance, SwapError>;
+
+    #[ink(message)]
+    fn get_amount_out(&self, token_in: AccountId, token_out: AccountId, amount_in: Balance) -> Balance;
+}
@@ -0,0 +1,59 @@
+use ink_prelude::vec::Vec;
+use ink_env::call::build_call;
+use ink_env::call::Call;
+use ink_env::call::ExecutionInput;
+use ink_env::call::Selector;
+use ink_env::CallFlags;
+use ink_env::DefaultEnvironment;
+use ink_storage::traits::PackedLayout;
+use ink_storage::traits::SpreadLayout;
+use brush::traits::AccountId;
+use brush::traits::Balance;
+
+#[cfg(feature = "std")]
+use ink_storage::traits::StorageLayout;
+
+/// Encoded call of a message of another contract, e.g. of `PoolManagerRef`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+#[cfg_attr(feature = "std", derive(StorageLayout, scale_info::TypeInfo))]
+pub struct Transaction{
+    pub callee: AccountId,
+    pub selector: [u8; 4],
+    pub input: Vec<u8>,
+    pub transferred_value: Balance,
+    pub gas_limit: u64,
+}
+
+/// Wrapper for already encoded arguments of the transaction,
+/// so they are passed to the call without being encoded again.
+#[derive(Clone)]
+pub struct CallInput<'a>(pub &'a [u8]);
+
+impl<'a> scale::Encode for CallInput<'a> {
+    fn encode_to<T: scale::Output + ?Sized>(&self, dest: &mut T) {
+        dest.write(self.0);
+    }
+}
+
+/// # Returns
+/// Returns a Ok(()) with success and ink_env::Error otherwise
+///
+/// # Arguments
+/// * `transaction` - Transaction to be called
+///
+/// # Description
+/// Helper function to call the encoded message of transaction, reentrancy is allowed,
+/// so the caller can execute transactions calling itself. Result of the message is not decoded.
+pub fn call_transaction(transaction: &Transaction) -> Result<(), ink_env::Error> {
+    build_call::<DefaultEnvironment>()
+        .call_type(
+            Call::new()
+                .callee(transaction.callee)
+                .gas_limit(transaction.gas_limit)
+                .transferred_value(transaction.transferred_value),
+        )
+        .exec_input(ExecutionInput::new(Selector::new(transaction.selector)).push_arg(CallInput(&transaction.input)))
+        .returns::<()>()
+        .call_flags(CallFlags::default().set_allow_reentry(true))
+        .fire()
+}
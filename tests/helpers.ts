@@ -97,3 +97,8 @@ export const bnArg = (value: number | string | number[] | Uint8Array | Buffer |
 }
 
 export const oneDay = () => (24 * 60 * 60 * 1000)
+
+// It finds selector of the message by its name, e.g. set_voting_delay for Governor::set_voting_delay
+export const messageSelector = (contract: Contract, message: string) => {
+  return contract.abi.messages.find(m => m.identifier.split('::').pop() === message).selector
+}
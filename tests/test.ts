@@ -1,5 +1,6 @@
-import {expect, setupContract, fromSigner} from './helpers'
+import {expect, setupContract, setupProxy, fromSigner, oneDay, bnArg, messageSelector} from './helpers'
 import BN from 'bn.js'
+import { decodeAddress } from '@polkadot/util-crypto'
 
 describe('Liquidity_pool_protocol', () => {
   async function setup() {
@@ -74,6 +75,24 @@ describe('Liquidity_pool_protocol', () => {
     await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.rejected
   })
 
+  it('Lend - supply cap exceeded', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // amount Alice wants to lend into the lending pool
+    const lend_amount = 10000;
+    // Alice approves lend amount for lending pool contract
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(lending_pool_manager.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // allow new asset (stablecoin1) for lending into the pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // limit the stablecoin1 pool below the lend amount
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setSupplyCap(stablecoin1.contract.address, lend_amount - 1)).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.rejected
+    // remove the supply cap
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setSupplyCap(stablecoin1.contract.address, null)).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.fulfilled
+  })
+
   it('Borrow - borrow asset successfully', async () => {
     const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
     // amount Alice wants to lend into the lending pool
@@ -243,4 +262,801 @@ describe('Liquidity_pool_protocol', () => {
     // Bob borrows assets with collateral
     await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrow(stablecoin1.contract.address, stablecoin2.contract.address, borrow_amount)).to.eventually.be.rejected
   })
+
+  it('Roles - only oracle updater sets conversion rates', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // ORACLE_UPDATER role
+    const oracle_updater = 3;
+    // Bob can not set conversion rates without the role
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.setConversionRate(stablecoin1.contract.address, stablecoin2.contract.address, 1)).to.eventually.be.rejected
+    // Charlie as risk admin grants the role to Bob
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.grantRole(oracle_updater, bob.address)).to.eventually.be.fulfilled
+    // Bob sets conversion rates
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.setConversionRate(stablecoin1.contract.address, stablecoin2.contract.address, 1)).to.eventually.be.fulfilled
+    // Bob still can not list new assets
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.rejected
+  })
+
+  it('Isolation - debt ceiling per borrowed asset', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // amount Alice wants to lend into the lending pool
+    const lend_amount = 10000;
+    // Alice approves lend amount for lending pool contract
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(lending_pool_manager.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // allow new asset (stablecoin1) for lending into the pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.fulfilled
+
+    // amount of collateral Bob uses for each borrow
+    const collateral_amount = 5000;
+    // allow new asset (stablecoin2) for colltarelization
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCollateralAllowance(stablecoin2.contract.address)).to.eventually.be.fulfilled
+    // Bob approves colateral amount for two borrows
+    await expect(fromSigner(stablecoin2.contract, bob.address).tx.approve(lending_pool_manager.contract.address, 2 * collateral_amount)).to.eventually.be.fulfilled
+    // set up conversion rates
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin1.contract.address, stablecoin2.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin2.contract.address, stablecoin1.contract.address, 1)).to.eventually.be.fulfilled
+    // isolate stablecoin2 as collateral
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCollateralIsolation(stablecoin2.contract.address, true)).to.eventually.be.fulfilled
+    // stablecoin1 has no debt ceiling against stablecoin2 yet, so it is not borrowable
+    await expect(lending_pool_manager.query.getIsolatedBorrowable(stablecoin2.contract.address, stablecoin1.contract.address)).to.have.output(false)
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrow(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount)).to.eventually.be.rejected
+    // allow stablecoin1 to be borrowed against stablecoin2 up to one loan
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setIsolatedBorrowable(stablecoin2.contract.address, stablecoin1.contract.address, 3500)).to.eventually.be.fulfilled
+    // Bob borrows assets with collateral
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrow(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount)).to.eventually.be.fulfilled
+    // the debt is tracked in units of stablecoin1
+    await expect(lending_pool_manager.query.getCollateralDebt(stablecoin2.contract.address, stablecoin1.contract.address)).to.have.output(3500)
+    // second borrow would exceed the ceiling
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrow(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount)).to.eventually.be.rejected
+  })
+
+  it('Categories - efficiency mode borrow', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // amount Alice wants to lend into the lending pool
+    const lend_amount = 10000;
+    // Alice approves lend amount for lending pool contract
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(lending_pool_manager.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // allow new asset (stablecoin1) for lending into the pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.fulfilled
+
+    // amount of collateral Bob uses
+    const collateral_amount = 5000;
+    // allow new asset (stablecoin2) for colltarelization
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCollateralAllowance(stablecoin2.contract.address)).to.eventually.be.fulfilled
+    // Bob approves colateral amount for lending pool contract
+    await expect(fromSigner(stablecoin2.contract, bob.address).tx.approve(lending_pool_manager.contract.address, collateral_amount)).to.eventually.be.fulfilled
+    // set up conversion rates
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin1.contract.address, stablecoin2.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin2.contract.address, stablecoin1.contract.address, 1)).to.eventually.be.fulfilled
+    // liquidation bonus above 20% of the debt is rejected
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCategory(1, {ltv: 90, liquidationThreshold: 95, liquidationBonus: 21, priceSource: null})).to.eventually.be.rejected
+    // loan to value has to stay below the liquidation threshold
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCategory(1, {ltv: 95, liquidationThreshold: 95, liquidationBonus: 1, priceSource: null})).to.eventually.be.rejected
+    // define the stablecoin category
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCategory(1, {ltv: 90, liquidationThreshold: 95, liquidationBonus: 1, priceSource: null})).to.eventually.be.fulfilled
+    // assign both stablecoins to the category
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetCategory(stablecoin1.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetCategory(stablecoin2.contract.address, 1)).to.eventually.be.fulfilled
+    // Bob enters the efficiency mode
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.setUserCategory(1)).to.eventually.be.fulfilled
+    // get Bob initial stablecoin1 balance
+    let bob_initial_asset1 = (await stablecoin1.query.balanceOf(bob.address)).output;
+    // Bob borrows assets with collateral
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrow(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount)).to.eventually.be.fulfilled
+    // Bob borrowed at the loan to value of the category
+    await expect(stablecoin1.query.balanceOf(bob.address)).to.have.output((bob_initial_asset1.add(new BN(4500))))
+  })
+
+  it('Market - frozen market lets lenders exit', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // amount Alice wants to lend into the lending pool
+    const lend_amount = 10000;
+    // Alice approves lend amount twice for lending pool contract
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(lending_pool_manager.contract.address, 2 * lend_amount)).to.eventually.be.fulfilled
+    // allow new asset (stablecoin1) for lending into the pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // get btoken of the stablecoin1 pool
+    let btoken_address = (await lending_pool_manager.query.getBtokenFromAsset(stablecoin1.contract.address)).output.asOk.toString();
+    // Bob can not change the market state without the listing admin role
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.setMarketState(stablecoin1.contract.address, 'Frozen')).to.eventually.be.rejected
+    // freeze the stablecoin1 market
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setMarketState(stablecoin1.contract.address, 'Frozen')).to.eventually.be.fulfilled
+    // frozen market accepts no new lends
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.rejected
+    // Alice can still withdraw half of her assets
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.withdraw(btoken_address, lend_amount / 2)).to.eventually.be.fulfilled
+    // pause the stablecoin1 market
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setMarketState(stablecoin1.contract.address, 'Paused')).to.eventually.be.fulfilled
+    // paused market stops withdrawals too
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.withdraw(btoken_address, lend_amount / 2)).to.eventually.be.rejected
+    // delist the stablecoin1 market
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setMarketState(stablecoin1.contract.address, 'Delisted')).to.eventually.be.fulfilled
+    // Alice exits the delisted market
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.withdraw(btoken_address, lend_amount / 2)).to.eventually.be.fulfilled
+    // delisting can not be reverted
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setMarketState(stablecoin1.contract.address, 'Active')).to.eventually.be.rejected
+  })
+
+  it('Pause - operations are paused independently', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // LEND and BORROW operation bits
+    const lend = 1;
+    const borrow = 2;
+    // amount Alice wants to lend into the lending pool
+    const lend_amount = 10000;
+    // Alice approves lend amount twice for lending pool contract
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(lending_pool_manager.contract.address, 2 * lend_amount)).to.eventually.be.fulfilled
+    // allow new asset (stablecoin1) for lending into the pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Bob can not pause without the pause guardian role
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.pauseOperations(lend, stablecoin1.contract.address)).to.eventually.be.rejected
+    // pause lending into the stablecoin1 pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.pauseOperations(lend, stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Alice can not lend stablecoin1
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.rejected
+    // unpausing borrows that are not paused fails
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.unpauseOperations(borrow, stablecoin1.contract.address)).to.eventually.be.rejected
+    // resume lending
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.unpauseOperations(lend, stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // pause every operation of the protocol
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.pause()).to.eventually.be.fulfilled
+    // Alice can not lend stablecoin1
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.rejected
+    // resume the protocol
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.unpause()).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.fulfilled
+  })
+
+  it('Roles - role members are enumerable', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // ORACLE_UPDATER role
+    const oracle_updater = 3;
+    // Charlie is the only oracle updater after deployment
+    await expect(lending_pool_manager.query.getRoleMemberCount(oracle_updater)).to.have.output(1)
+    await expect(lending_pool_manager.query.getRoleMember(oracle_updater, 0)).to.have.output(charlie.address)
+    // Charlie grants the role to Bob
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.grantRole(oracle_updater, bob.address)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.query.getRoleMemberCount(oracle_updater)).to.have.output(2)
+    await expect(lending_pool_manager.query.getRoleMember(oracle_updater, 1)).to.have.output(bob.address)
+    // Charlie renounces the role, Bob takes his place in the list
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.renounceRole(oracle_updater, charlie.address)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.query.getRoleMemberCount(oracle_updater)).to.have.output(1)
+    await expect(lending_pool_manager.query.getRoleMember(oracle_updater, 0)).to.have.output(bob.address)
+    // Bob's role is revoked
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.revokeRole(oracle_updater, bob.address)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.query.getRoleMemberCount(oracle_updater)).to.have.output(0)
+    await expect(lending_pool_manager.query.getRoleMember(oracle_updater, 0)).to.have.output(null)
+  })
+
+  it('Roles - time-bound grant becomes permanent', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // ORACLE_UPDATER role
+    const oracle_updater = 3;
+    // grant expires in one day
+    const expiry = Date.now() + oneDay();
+    // expiry in the past is rejected
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.grantRoleUntil(oracle_updater, bob.address, Date.now() - oneDay())).to.eventually.be.rejected
+    // Charlie grants the role to Bob for one day
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.grantRoleUntil(oracle_updater, bob.address, expiry)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.query.hasRole(oracle_updater, bob.address)).to.have.output(true)
+    await expect(lending_pool_manager.query.getRoleExpiry(oracle_updater, bob.address)).to.have.output(expiry)
+    // Bob sets conversion rates
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.setConversionRate(stablecoin1.contract.address, stablecoin2.contract.address, 1)).to.eventually.be.fulfilled
+    // Charlie makes the grant permanent
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.grantRole(oracle_updater, bob.address)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.query.getRoleExpiry(oracle_updater, bob.address)).to.have.output(null)
+    // permanent grant can not be granted again
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.grantRole(oracle_updater, bob.address)).to.eventually.be.rejected
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.grantRoleUntil(oracle_updater, bob.address, expiry)).to.eventually.be.rejected
+  })
+
+  it('Ownership - two-step transfer', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // btoken owned by its deployer
+    let btoken = await setupContract('btoken_contract', 'new', '', '')
+    let owner = btoken.defaultSigner
+    // only the owner starts the transfer
+    await expect(fromSigner(btoken.contract, bob.address).tx.transferOwnership(bob.address)).to.eventually.be.rejected
+    // owner starts the transfer to Bob
+    await expect(fromSigner(btoken.contract, owner.address).tx.transferOwnership(bob.address)).to.eventually.be.fulfilled
+    await expect(btoken.query.pendingOwner()).to.have.output(bob.address)
+    // ownership does not move until Bob accepts it
+    await expect(btoken.query.owner()).to.have.output(owner.address)
+    // owner cancels the transfer
+    await expect(fromSigner(btoken.contract, owner.address).tx.cancelOwnershipTransfer()).to.eventually.be.fulfilled
+    await expect(btoken.query.pendingOwner()).to.have.output(null)
+    // Bob can not accept canceled transfer
+    await expect(fromSigner(btoken.contract, bob.address).tx.acceptOwnership()).to.eventually.be.rejected
+    // owner starts the transfer to Bob again
+    await expect(fromSigner(btoken.contract, owner.address).tx.transferOwnership(bob.address)).to.eventually.be.fulfilled
+    // only the pending owner accepts the transfer
+    await expect(fromSigner(btoken.contract, charlie.address).tx.acceptOwnership()).to.eventually.be.rejected
+    await expect(fromSigner(btoken.contract, bob.address).tx.acceptOwnership()).to.eventually.be.fulfilled
+    await expect(btoken.query.owner()).to.have.output(bob.address)
+    await expect(btoken.query.pendingOwner()).to.have.output(null)
+  })
+
+  it('Governor - proposal executed after vote and timelock', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // governance token minted to its deployer
+    let governance_token = await setupContract('governance_token_contract', 'new', `Governance`, `GOV`, 1000000)
+    let voter = governance_token.defaultSigner
+    // governor with no voting delay, two blocks of voting, 4% quorum, no proposal threshold and no timelock
+    let governor = await setupContract('governor_contract', 'new', governance_token.contract.address, 0, 2, 4, 0, 0)
+    // proposal calls set_voting_delay of the governor itself
+    const transaction = {callee: governor.contract.address, selector: messageSelector(governor.contract, 'set_voting_delay'), input: bnArg(5, 4), transferredValue: 0, gasLimit: 0}
+    // settings are changed only through proposals
+    await expect(governor.tx.setVotingDelay(5)).to.eventually.be.rejected
+    // proposal without transactions is rejected
+    await expect(fromSigner(governor.contract, voter.address).tx.propose([], 'Empty')).to.eventually.be.rejected
+    // voter proposes the change
+    await expect(fromSigner(governor.contract, voter.address).tx.propose([transaction], 'Set voting delay')).to.eventually.be.fulfilled
+    // voter votes for the proposal with his whole balance
+    await expect(fromSigner(governor.contract, voter.address).tx.castVote(1, 'For')).to.eventually.be.fulfilled
+    await expect(governor.query.hasVoted(1, voter.address)).to.have.output(true)
+    // each account votes only once
+    await expect(fromSigner(governor.contract, voter.address).tx.castVote(1, 'For')).to.eventually.be.rejected
+    // proposal can not be queued during the voting period
+    await expect(fromSigner(governor.contract, voter.address).tx.queue(1)).to.eventually.be.rejected
+    // let the voting period end
+    await expect(fromSigner(governance_token.contract, voter.address).tx.transfer(bob.address, 1, [])).to.eventually.be.fulfilled
+    // queue and execute the succeeded proposal
+    await expect(fromSigner(governor.contract, voter.address).tx.queue(1)).to.eventually.be.fulfilled
+    await expect(fromSigner(governor.contract, voter.address).tx.execute(1)).to.eventually.be.fulfilled
+    // the governor changed its own setting
+    await expect(governor.query.getVotingDelay()).to.have.output(5)
+    // executed proposal can not be executed again
+    await expect(fromSigner(governor.contract, voter.address).tx.execute(1)).to.eventually.be.rejected
+  })
+
+  it('Multisig - transaction executed after threshold', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // 2 of 3 multisig
+    let multisig = await setupContract('multisig_contract', 'new', [alice.address, bob.address, charlie.address], 2)
+    // transaction adds Dave as owner of the multisig
+    const transaction = {callee: multisig.contract.address, selector: messageSelector(multisig.contract, 'add_owner'), input: decodeAddress(dave.address), transferredValue: 0, gasLimit: 0}
+    // owners are changed only by the multisig itself
+    await expect(fromSigner(multisig.contract, alice.address).tx.addOwner(dave.address)).to.eventually.be.rejected
+    // only owners submit transactions
+    await expect(fromSigner(multisig.contract, dave.address).tx.submitTransaction(transaction)).to.eventually.be.rejected
+    // Alice submits and confirms the transaction
+    await expect(fromSigner(multisig.contract, alice.address).tx.submitTransaction(transaction)).to.eventually.be.fulfilled
+    await expect(multisig.query.getConfirmationCount(1)).to.have.output(1)
+    // one confirmation is below the threshold
+    await expect(fromSigner(multisig.contract, alice.address).tx.executeTransaction(1)).to.eventually.be.rejected
+    // Bob confirms and revokes his confirmation
+    await expect(fromSigner(multisig.contract, bob.address).tx.confirmTransaction(1)).to.eventually.be.fulfilled
+    await expect(fromSigner(multisig.contract, bob.address).tx.revokeConfirmation(1)).to.eventually.be.fulfilled
+    await expect(fromSigner(multisig.contract, alice.address).tx.executeTransaction(1)).to.eventually.be.rejected
+    // Charlie confirms the transaction
+    await expect(fromSigner(multisig.contract, charlie.address).tx.confirmTransaction(1)).to.eventually.be.fulfilled
+    await expect(fromSigner(multisig.contract, charlie.address).tx.confirmTransaction(1)).to.eventually.be.rejected
+    // threshold is reached, Bob executes the transaction
+    await expect(fromSigner(multisig.contract, bob.address).tx.executeTransaction(1)).to.eventually.be.fulfilled
+    await expect(multisig.query.isExecuted(1)).to.have.output(true)
+    await expect(multisig.query.isOwner(dave.address)).to.have.output(true)
+    // executed transaction can not be executed again
+    await expect(fromSigner(multisig.contract, bob.address).tx.executeTransaction(1)).to.eventually.be.rejected
+  })
+
+  it('Guardrails - bounded auction and deleverage parameters', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // allow new asset (stablecoin2) for colltarelization
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCollateralAllowance(stablecoin2.contract.address)).to.eventually.be.fulfilled
+    // deleverage fee can be at most 5%
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setParameterBounds('DeleverageFee', {min: 0, max: 500, maxChange: 0, window: 0})).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setDeleverageFee(stablecoin2.contract.address, 501)).to.eventually.be.rejected
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setDeleverageFee(stablecoin2.contract.address, 500)).to.eventually.be.fulfilled
+    // auction floor can be at least 70% of the oracle price
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setParameterBounds('AuctionFloor', {min: 7000, max: 10000, maxChange: 0, window: 0})).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAuctionConfig(stablecoin2.contract.address, {startPremium: 12000, floor: 5000, duration: 3600000})).to.eventually.be.rejected
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAuctionConfig(stablecoin2.contract.address, {startPremium: 12000, floor: 7000, duration: 3600000})).to.eventually.be.fulfilled
+    // maximal liquidation bonus can be at most 10%
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setParameterBounds('MaxLiquidationBonus', {min: 0, max: 1000, maxChange: 0, window: 0})).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setLiquidationBonusCurve(stablecoin2.contract.address, {minBonus: 100, maxBonus: 1500, maxBonusHealth: 5000})).to.eventually.be.rejected
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setLiquidationBonusCurve(stablecoin2.contract.address, {minBonus: 100, maxBonus: 1000, maxBonusHealth: 5000})).to.eventually.be.fulfilled
+    // rates of fixed terms have to stay between 1% and 20%
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setParameterBounds('TermRate', {min: 100, max: 2000, maxChange: 0, window: 0})).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setTermRate(oneDay(), 5000)).to.eventually.be.rejected
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setTermRate(oneDay(), 500)).to.eventually.be.fulfilled
+    // grace period has to stay between one and seven days
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setParameterBounds('GracePeriod', {min: oneDay(), max: 7 * oneDay(), maxChange: 0, window: 0})).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setGracePeriod(0)).to.eventually.be.rejected
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setGracePeriod(2 * oneDay())).to.eventually.be.fulfilled
+  })
+
+  it('Circuit breaker - block outflow pauses the market', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // WITHDRAW operation bit
+    const withdraw = 8;
+    // amount Alice wants to lend into the lending pool
+    const lend_amount = 20000;
+    // Alice approves lend amount for lending pool contract
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(lending_pool_manager.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // allow new asset (stablecoin1) for lending into the pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // get btoken of the stablecoin1 pool
+    let btoken_address = (await lending_pool_manager.query.getBtokenFromAsset(stablecoin1.contract.address)).output.asOk.toString();
+
+    // amount of collateral Bob uses for each borrow
+    const collateral_amount = 5000;
+    // allow new asset (stablecoin2) for colltarelization
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCollateralAllowance(stablecoin2.contract.address)).to.eventually.be.fulfilled
+    // Bob approves colateral amount for two borrows
+    await expect(fromSigner(stablecoin2.contract, bob.address).tx.approve(lending_pool_manager.contract.address, 2 * collateral_amount)).to.eventually.be.fulfilled
+    // set up conversion rates
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin1.contract.address, stablecoin2.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin2.contract.address, stablecoin1.contract.address, 1)).to.eventually.be.fulfilled
+    // at most 1000 stablecoin1 can leave the pool in one block
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCircuitBreaker(stablecoin1.contract.address, {maxPriceDeviation: 0, priceWindow: 0, maxBlockOutflow: 1000})).to.eventually.be.fulfilled
+    // guardian pauses withdrawals on his own
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.pauseOperations(withdraw, stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Bob's borrow crosses the maximum, it succeeds and trips the circuit breaker
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrow(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.query.isCircuitBreakerTripped(stablecoin1.contract.address)).to.have.output(true)
+    // the market stays paused
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrow(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount)).to.eventually.be.rejected
+    // only the guardian clears the circuit breaker
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.clearCircuitBreaker(stablecoin1.contract.address)).to.eventually.be.rejected
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.clearCircuitBreaker(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.query.isCircuitBreakerTripped(stablecoin1.contract.address)).to.have.output(false)
+    // withdrawals paused by the guardian stay paused
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.withdraw(btoken_address, 100)).to.eventually.be.rejected
+    // borrowing resumed
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrow(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount)).to.eventually.be.fulfilled
+  })
+
+  it('Rate limit - bounded outflow set by risk admin', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // RISK_ADMIN role
+    const risk_admin = 2;
+    // amount Alice wants to lend into the lending pool
+    const lend_amount = 10000;
+    // Alice approves lend amount for lending pool contract
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(lending_pool_manager.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // allow new asset (stablecoin1) for lending into the pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // get btoken of the stablecoin1 pool
+    let btoken_address = (await lending_pool_manager.query.getBtokenFromAsset(stablecoin1.contract.address)).output.asOk.toString();
+    // Bob can not limit the outflow without the risk admin role
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.setRateLimit(stablecoin1.contract.address, {capacity: 1000, refillPeriod: oneDay()})).to.eventually.be.rejected
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.grantRole(risk_admin, bob.address)).to.eventually.be.fulfilled
+    // capacity has to stay between 500 and 5000
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setParameterBounds('RateLimitCapacity', {min: 500, max: 5000, maxChange: 0, window: 0})).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.setRateLimit(stablecoin1.contract.address, {capacity: 100, refillPeriod: oneDay()})).to.eventually.be.rejected
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.setRateLimit(stablecoin1.contract.address, {capacity: 1000, refillPeriod: oneDay()})).to.eventually.be.fulfilled
+    // removing the limit would make the capacity unlimited
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.setRateLimit(stablecoin1.contract.address, null)).to.eventually.be.rejected
+    // Alice can not withdraw above the capacity
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.withdraw(btoken_address, 2000)).to.eventually.be.rejected
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.withdraw(btoken_address, 1000)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.query.getRemainingOutflowCapacity(stablecoin1.contract.address)).to.have.output(0)
+  })
+
+  it('Withdrawal queue - direct withdrawals wait for the queue', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // btoken deployed only for its abi
+    let btoken = await setupContract('btoken_contract', 'new', '', '')
+    // amount Alice wants to lend into the lending pool
+    const lend_amount = 10000;
+    // Alice approves lend amount and later lend for lending pool contract
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(lending_pool_manager.contract.address, lend_amount + 3000)).to.eventually.be.fulfilled
+    // allow new asset (stablecoin1) for lending into the pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // get btoken of the stablecoin1 pool
+    let btoken_address = (await lending_pool_manager.query.getBtokenFromAsset(stablecoin1.contract.address)).output.asOk.toString();
+    let pool_btoken = setupProxy(btoken.contract, {address: btoken_address, signer: alice.address})
+
+    // Bob borrows 7000 and leaves 3000 in the pool
+    const collateral_amount = 10000;
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCollateralAllowance(stablecoin2.contract.address)).to.eventually.be.fulfilled
+    await expect(fromSigner(stablecoin2.contract, bob.address).tx.approve(lending_pool_manager.contract.address, collateral_amount)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin1.contract.address, stablecoin2.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin2.contract.address, stablecoin1.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrow(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount)).to.eventually.be.fulfilled
+
+    // Alice requests more than the pool has, the rest of the request waits in the queue
+    await expect(fromSigner(pool_btoken, alice.address).tx.approve(lending_pool_manager.contract.address, 5000)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.requestWithdrawal(btoken_address, 5000)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.query.getQueueLength(stablecoin1.contract.address)).to.have.output(1)
+    // direct withdrawals can not jump the queue
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.withdraw(btoken_address, 100)).to.eventually.be.rejected
+    // new liquidity fills the queue
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, 3000)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.query.getQueueLength(stablecoin1.contract.address)).to.have.output(0)
+    await expect(lending_pool_manager.query.getPendingWithdrawal(stablecoin1.contract.address, alice.address)).to.have.output(0)
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.withdraw(btoken_address, 100)).to.eventually.be.fulfilled
+  })
+
+  it('Bad debt - shortfall covered by reserves and written down', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // amount Alice wants to lend into the lending pool
+    const lend_amount = 10000;
+    // Alice approves lend amount and protocol reserves for lending pool contract
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(lending_pool_manager.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // allow new asset (stablecoin1) for lending into the pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // treasury funds 1000 of protocol reserves
+    const reserves_amount = 1000;
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.transfer(charlie.address, reserves_amount, [])).to.eventually.be.fulfilled
+    await expect(fromSigner(stablecoin1.contract, charlie.address).tx.approve(lending_pool_manager.contract.address, reserves_amount)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.depositProtocolReserves(stablecoin1.contract.address, reserves_amount)).to.eventually.be.rejected
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.depositProtocolReserves(stablecoin1.contract.address, reserves_amount)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.query.getProtocolReserves(stablecoin1.contract.address)).to.have.output(reserves_amount)
+
+    // Bob borrows 7000 with 1000 stablecoin2 worth 10 stablecoin1 each
+    const collateral_amount = 1000;
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCollateralAllowance(stablecoin2.contract.address)).to.eventually.be.fulfilled
+    await expect(fromSigner(stablecoin2.contract, bob.address).tx.approve(lending_pool_manager.contract.address, collateral_amount)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin1.contract.address, stablecoin2.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin2.contract.address, stablecoin1.contract.address, 10)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrow(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount)).to.eventually.be.fulfilled
+    // auctions of stablecoin2 reach their floor at once
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAuctionConfig(stablecoin2.contract.address, {startPremium: 12000, floor: 8000, duration: 1})).to.eventually.be.fulfilled
+
+    // the first loan gets Id 2
+    // collateral price halves, the debt is no longer backed
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin2.contract.address, stablecoin1.contract.address, 5)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, dave.address).tx.liquidateLoan({u8: 2})).to.eventually.be.fulfilled
+    // unsold collateral leaves the shortfall to the reserves and lenders
+    await expect(fromSigner(lending_pool_manager.contract, dave.address).tx.closeAuction({u8: 2})).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.query.getProtocolReserves(stablecoin1.contract.address)).to.have.output(0)
+    let bad_debt = (await lending_pool_manager.query.getBadDebt(stablecoin1.contract.address)).output
+    expect(bad_debt.coveredByReserves.toNumber()).to.equal(reserves_amount)
+    expect(bad_debt.writtenDown.toNumber()).to.equal(bad_debt.totalShortfall.toNumber() - reserves_amount)
+    expect(bad_debt.writtenDown.toNumber()).to.be.above(0)
+  })
+
+  it('Safety module - staking resets cooldown and stops after full slash', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // stablecoin1 is staked and paid as rewards, Bob is the slasher, there is no cooldown period
+    let safety_module = await setupContract('safety_module_contract', 'new', stablecoin1.contract.address, stablecoin1.contract.address, bob.address, 0, oneDay(), 100, null, null)
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(safety_module.contract.address, 2000)).to.eventually.be.fulfilled
+    // Alice stakes and starts the cooldown
+    await expect(fromSigner(safety_module.contract, alice.address).tx.stake(1000)).to.eventually.be.fulfilled
+    await expect(fromSigner(safety_module.contract, alice.address).tx.cooldown()).to.eventually.be.fulfilled
+    // staking more resets the cooldown, so the new stake can't leave at once
+    await expect(fromSigner(safety_module.contract, alice.address).tx.stake(100)).to.eventually.be.fulfilled
+    await expect(fromSigner(safety_module.contract, alice.address).tx.unstake(100)).to.eventually.be.rejected
+    await expect(fromSigner(safety_module.contract, alice.address).tx.cooldown()).to.eventually.be.fulfilled
+    await expect(fromSigner(safety_module.contract, alice.address).tx.unstake(100)).to.eventually.be.fulfilled
+    // only the slasher slashes, the whole stake is taken
+    await expect(fromSigner(safety_module.contract, alice.address).tx.slash(1000, alice.address)).to.eventually.be.rejected
+    await expect(fromSigner(safety_module.contract, bob.address).tx.slash(1000, bob.address)).to.eventually.be.fulfilled
+    await expect(safety_module.query.getTotalStaked()).to.have.output(0)
+    // worthless shares are left, new stake would belong to them
+    await expect(fromSigner(safety_module.contract, alice.address).tx.stake(100)).to.eventually.be.rejected
+  })
+
+  it('Shutdown - redemptions start after every loan is settled', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // amount Alice wants to lend into the lending pool
+    const lend_amount = 10000;
+    // Alice approves lend amount for lending pool contract
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(lending_pool_manager.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // allow new asset (stablecoin1) for lending into the pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // get btoken of the stablecoin1 pool
+    let btoken_address = (await lending_pool_manager.query.getBtokenFromAsset(stablecoin1.contract.address)).output.asOk.toString();
+
+    // Bob borrows 3500 with 5000 stablecoin2
+    const collateral_amount = 5000;
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCollateralAllowance(stablecoin2.contract.address)).to.eventually.be.fulfilled
+    await expect(fromSigner(stablecoin2.contract, bob.address).tx.approve(lending_pool_manager.contract.address, collateral_amount)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin1.contract.address, stablecoin2.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin2.contract.address, stablecoin1.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrow(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount)).to.eventually.be.fulfilled
+
+    // admin shuts the protocol down
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.shutdown()).to.eventually.be.fulfilled
+    // the loan of Bob is a claim of the pool outside of it, Alice has to wait
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.redeemSettlement(btoken_address, 1000)).to.eventually.be.rejected
+    // anyone settles the loan, the first loan gets Id 2
+    await expect(fromSigner(lending_pool_manager.contract, dave.address).tx.settleLoan({u8: 2})).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.query.getPoolCollateral(stablecoin1.contract.address, stablecoin2.contract.address)).to.have.output(3501)
+    // Alice redeems her share of the pool and of the seized collateral
+    let alice_initial_asset2 = (await stablecoin2.query.balanceOf(alice.address)).output;
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.redeemSettlement(btoken_address, lend_amount)).to.eventually.be.fulfilled
+    await expect(stablecoin2.query.balanceOf(alice.address)).to.have.output(alice_initial_asset2.add(new BN(3501)))
+  })
+
+  it('Auction - unsold collateral is sold by the pool', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // amount Alice wants to lend into the lending pool
+    const lend_amount = 10000;
+    // Alice approves lend amount and later purchase for lending pool contract
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(lending_pool_manager.contract.address, lend_amount + 400)).to.eventually.be.fulfilled
+    // allow new asset (stablecoin1) for lending into the pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.fulfilled
+
+    // Bob borrows 7000 with 1000 stablecoin2 worth 10 stablecoin1 each
+    const collateral_amount = 1000;
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCollateralAllowance(stablecoin2.contract.address)).to.eventually.be.fulfilled
+    await expect(fromSigner(stablecoin2.contract, bob.address).tx.approve(lending_pool_manager.contract.address, collateral_amount)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin1.contract.address, stablecoin2.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin2.contract.address, stablecoin1.contract.address, 10)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrow(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount)).to.eventually.be.fulfilled
+    // auctions of stablecoin2 reach their floor at once
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAuctionConfig(stablecoin2.contract.address, {startPremium: 12000, floor: 8000, duration: 1})).to.eventually.be.fulfilled
+
+    // collateral price halves, the loan is liquidated and nobody bids, the first loan gets Id 2
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin2.contract.address, stablecoin1.contract.address, 5)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, dave.address).tx.liquidateLoan({u8: 2})).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, dave.address).tx.closeAuction({u8: 2})).to.eventually.be.fulfilled
+    // the market keeps the collateral left after the liquidation reward, 1% of the debt paid in collateral
+    await expect(lending_pool_manager.query.getPoolCollateral(stablecoin1.contract.address, stablecoin2.contract.address)).to.have.output(986)
+
+    // Alice buys 100 of it at the floor price, 80% of 500
+    let alice_initial_asset2 = (await stablecoin2.query.balanceOf(alice.address)).output;
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.buyPoolCollateral(stablecoin1.contract.address, stablecoin2.contract.address, 100)).to.eventually.be.fulfilled
+    await expect(stablecoin2.query.balanceOf(alice.address)).to.have.output(alice_initial_asset2.add(new BN(100)))
+    await expect(lending_pool_manager.query.getPoolCollateral(stablecoin1.contract.address, stablecoin2.contract.address)).to.have.output(886)
+  })
+
+  it('Liquidation - bonus curve is capped and paid on the debt', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // amount Alice wants to lend into the lending pool
+    const lend_amount = 10000;
+    // Alice approves lend amount for lending pool contract
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(lending_pool_manager.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // allow new asset (stablecoin1) for lending into the pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.fulfilled
+
+    // Bob borrows 7000 with 1000 stablecoin2 worth 10 stablecoin1 each
+    const collateral_amount = 1000;
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCollateralAllowance(stablecoin2.contract.address)).to.eventually.be.fulfilled
+    await expect(fromSigner(stablecoin2.contract, bob.address).tx.approve(lending_pool_manager.contract.address, collateral_amount)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin1.contract.address, stablecoin2.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin2.contract.address, stablecoin1.contract.address, 10)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrow(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount)).to.eventually.be.fulfilled
+
+    // maximal bonus above 20% is rejected
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setLiquidationBonusCurve(stablecoin2.contract.address, {minBonus: 100, maxBonus: 2500, maxBonusHealth: 5000})).to.eventually.be.rejected
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setLiquidationBonusCurve(stablecoin2.contract.address, {minBonus: 100, maxBonus: 1000, maxBonusHealth: 5000})).to.eventually.be.fulfilled
+    // collateral price halves, health of the loan is 5356 basis points, the first loan gets Id 2
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin2.contract.address, stablecoin1.contract.address, 5)).to.eventually.be.fulfilled
+    expect((await lending_pool_manager.query.getLiquidationBonus({u8: 2})).output.asOk.toNumber()).to.equal(935)
+    // Dave gets 9.35% of the 7001 debt in collateral worth 5 each
+    let dave_initial_asset2 = (await stablecoin2.query.balanceOf(dave.address)).output;
+    await expect(fromSigner(lending_pool_manager.contract, dave.address).tx.liquidateLoan({u8: 2})).to.eventually.be.fulfilled
+    await expect(stablecoin2.query.balanceOf(dave.address)).to.have.output(dave_initial_asset2.add(new BN(130)))
+  })
+
+  it('Liquidation - batch keeps the liquidation when the purchase fails', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // amount Alice wants to lend into the lending pool
+    const lend_amount = 10000;
+    // Alice approves lend amount for lending pool contract
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(lending_pool_manager.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // allow new asset (stablecoin1) for lending into the pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.fulfilled
+
+    // Bob borrows 7000 with 1000 stablecoin2 worth 10 stablecoin1 each
+    const collateral_amount = 1000;
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCollateralAllowance(stablecoin2.contract.address)).to.eventually.be.fulfilled
+    await expect(fromSigner(stablecoin2.contract, bob.address).tx.approve(lending_pool_manager.contract.address, collateral_amount)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin1.contract.address, stablecoin2.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin2.contract.address, stablecoin1.contract.address, 10)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrow(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount)).to.eventually.be.fulfilled
+    // collateral price halves
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin2.contract.address, stablecoin1.contract.address, 5)).to.eventually.be.fulfilled
+
+    // Dave has no stablecoin1 to pay for the collateral, the first loan gets Id 2
+    let result = (await fromSigner(lending_pool_manager.contract, dave.address).query.liquidateBatch([[{u8: 2}, 1000]])).output[0]
+    expect(result.liquidation.isOk).to.equal(true)
+    expect(result.purchase.unwrap().isErr).to.equal(true)
+    // the loan is still liquidated and Dave gets the bonus, 1% of the debt in collateral
+    let dave_initial_asset2 = (await stablecoin2.query.balanceOf(dave.address)).output;
+    await expect(fromSigner(lending_pool_manager.contract, dave.address).tx.liquidateBatch([[{u8: 2}, 1000]])).to.eventually.be.fulfilled
+    await expect(stablecoin2.query.balanceOf(dave.address)).to.have.output(dave_initial_asset2.add(new BN(14)))
+    expect((await lending_pool_manager.query.getAuction({u8: 2})).output.isSome).to.equal(true)
+  })
+
+  it('Deleverage - pool buys the collateral without a swap venue', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // amount Alice wants to lend into the lending pool
+    const lend_amount = 10000;
+    // Alice approves lend amount for lending pool contract
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(lending_pool_manager.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // allow new asset (stablecoin1) for lending into the pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.fulfilled
+
+    // Bob borrows 3500 with 5000 stablecoin2
+    const collateral_amount = 5000;
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCollateralAllowance(stablecoin2.contract.address)).to.eventually.be.fulfilled
+    await expect(fromSigner(stablecoin2.contract, bob.address).tx.approve(lending_pool_manager.contract.address, collateral_amount)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin1.contract.address, stablecoin2.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin2.contract.address, stablecoin1.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrow(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount)).to.eventually.be.fulfilled
+
+    // without a swap venue the pool buys the collateral, the first loan gets Id 2
+    await expect(lending_pool_manager.query.getSwapVenue(stablecoin2.contract.address, stablecoin1.contract.address)).to.have.output(null)
+    let total_asset = (await lending_pool_manager.query.getTotalAsset(stablecoin1.contract.address)).output.asOk.toNumber()
+    let debt = (await lending_pool_manager.query.getLoanDebt({u8: 2})).output.asOk.toNumber()
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.repayWithCollateral({u8: 2}, 1000)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.query.getPoolCollateral(stablecoin1.contract.address, stablecoin2.contract.address)).to.have.output(1000)
+    // the debt went down and lenders keep the price the pool paid
+    expect((await lending_pool_manager.query.getLoanDebt({u8: 2})).output.asOk.toNumber()).to.be.below(debt)
+    expect((await lending_pool_manager.query.getTotalAsset(stablecoin1.contract.address)).output.asOk.toNumber()).to.be.at.least(total_asset)
+  })
+
+  it('Collateral swap - paused with borrows and rejects zero amount', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // BORROW operation bit
+    const borrow = 2;
+    // amount Alice wants to lend into the lending pool
+    const lend_amount = 10000;
+    // Alice approves lend amount for lending pool contract
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(lending_pool_manager.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // allow new asset (stablecoin1) for lending into the pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.fulfilled
+
+    // Bob borrows 3500 with 5000 stablecoin2 and approves 6000 more for the swap
+    const collateral_amount = 5000;
+    const new_collateral_amount = 6000;
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCollateralAllowance(stablecoin2.contract.address)).to.eventually.be.fulfilled
+    await expect(fromSigner(stablecoin2.contract, bob.address).tx.approve(lending_pool_manager.contract.address, collateral_amount + new_collateral_amount)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin1.contract.address, stablecoin2.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin2.contract.address, stablecoin1.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrow(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount)).to.eventually.be.fulfilled
+
+    // collateral swap is paused with borrows, the first loan gets Id 2
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.pauseOperations(borrow, stablecoin1.contract.address)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.swapCollateral({u8: 2}, stablecoin2.contract.address, new_collateral_amount)).to.eventually.be.rejected
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.unpauseOperations(borrow, stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // the loan can not be left without collateral
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.swapCollateral({u8: 2}, stablecoin2.contract.address, 0)).to.eventually.be.rejected
+    // Bob replaces his collateral, the old one is returned
+    let bob_initial_asset2 = (await stablecoin2.query.balanceOf(bob.address)).output;
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.swapCollateral({u8: 2}, stablecoin2.contract.address, new_collateral_amount)).to.eventually.be.fulfilled
+    await expect(stablecoin2.query.balanceOf(bob.address)).to.have.output(bob_initial_asset2.sub(new BN(new_collateral_amount - collateral_amount)))
+  })
+
+  it('Refinance - fixed-term loans are rejected and slippage is set by the risk admin', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // amount Alice wants to lend into the lending pool
+    const lend_amount = 10000;
+    // Alice approves lend amount for lending pool contract
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(lending_pool_manager.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // allow new asset (stablecoin1) for lending into the pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.fulfilled
+
+    // Bob borrows for one day and open-ended, each loan with 5000 stablecoin2
+    const collateral_amount = 5000;
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCollateralAllowance(stablecoin2.contract.address)).to.eventually.be.fulfilled
+    await expect(fromSigner(stablecoin2.contract, bob.address).tx.approve(lending_pool_manager.contract.address, 2 * collateral_amount)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin1.contract.address, stablecoin2.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin2.contract.address, stablecoin1.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setTermRate(oneDay(), 500)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrowWithTerm(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount, oneDay())).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrow(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount)).to.eventually.be.fulfilled
+
+    // the fixed-term loan with Id 2 would keep its maturity and rate in the new asset
+    let fixed_term = (await fromSigner(lending_pool_manager.contract, bob.address).query.refinance({u8: 2}, stablecoin2.contract.address)).output
+    expect(fixed_term.asErr.isFixedTermLoan).to.equal(true)
+    // only the risk admin sets the slippage of the pair, slippage of the whole borrowed amount is not possible
+    await expect(lending_pool_manager.query.getRefinanceSlippage(stablecoin2.contract.address, stablecoin1.contract.address)).to.have.output(100)
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.setRefinanceSlippage(stablecoin2.contract.address, stablecoin1.contract.address, 50)).to.eventually.be.rejected
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setRefinanceSlippage(stablecoin2.contract.address, stablecoin1.contract.address, 10000)).to.eventually.be.rejected
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setRefinanceSlippage(stablecoin2.contract.address, stablecoin1.contract.address, 50)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.query.getRefinanceSlippage(stablecoin2.contract.address, stablecoin1.contract.address)).to.have.output(50)
+    // the slippage respects its guardrails
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setParameterBounds('RefinanceSlippage', {min: 0, max: 300, maxChange: 0, window: 0})).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setRefinanceSlippage(stablecoin2.contract.address, stablecoin1.contract.address, 301)).to.eventually.be.rejected
+    // the open-ended loan can't be rolled over to the rate of a fixed term
+    let open_ended = (await fromSigner(lending_pool_manager.contract, bob.address).query.rollOver({u8: 3}, oneDay())).output
+    expect(open_ended.asErr.isOpenEndedLoan).to.equal(true)
+  })
+
+  it('Fixed term - expired loan is liquidated and rolled over loan is not', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // amount Alice wants to lend into the lending pool
+    const lend_amount = 10000;
+    // Alice approves lend amount for lending pool contract
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(lending_pool_manager.contract.address, lend_amount)).to.eventually.be.fulfilled
+    // allow new asset (stablecoin1) for lending into the pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    // Alice lends stablecoin1 to the lending pool
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, lend_amount)).to.eventually.be.fulfilled
+
+    // Bob borrows twice for the shortest term, each loan with 5000 stablecoin2
+    const collateral_amount = 5000;
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCollateralAllowance(stablecoin2.contract.address)).to.eventually.be.fulfilled
+    await expect(fromSigner(stablecoin2.contract, bob.address).tx.approve(lending_pool_manager.contract.address, 2 * collateral_amount)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin1.contract.address, stablecoin2.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin2.contract.address, stablecoin1.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setTermRate(1, 500)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setTermRate(oneDay(), 500)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrowWithTerm(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount, 1)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrowWithTerm(stablecoin1.contract.address, stablecoin2.contract.address, collateral_amount, 1)).to.eventually.be.fulfilled
+
+    // Bob rolls the loan with Id 3 over for one day within the grace period, he pays the accrued interest
+    await expect(fromSigner(stablecoin1.contract, bob.address).tx.approve(lending_pool_manager.contract.address, 100)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.rollOver({u8: 3}, oneDay())).to.eventually.be.fulfilled
+    // grace period is bounded
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setGracePeriod(2592001)).to.eventually.be.rejected
+    // without grace period the loan with Id 2 is expired
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setGracePeriod(0)).to.eventually.be.fulfilled
+    // the expired loan can't be rolled over and is liquidated although it is healthy
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.rollOver({u8: 2}, oneDay())).to.eventually.be.rejected
+    await expect(fromSigner(lending_pool_manager.contract, dave.address).tx.liquidateLoan({u8: 2})).to.eventually.be.fulfilled
+    // the rolled over loan is not expired
+    await expect(fromSigner(lending_pool_manager.contract, dave.address).tx.liquidateLoan({u8: 3})).to.eventually.be.rejected
+  })
+
+  it('Safety module - dust transfer does not block unstake', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // stablecoin1 is staked and paid as rewards, Bob is the slasher, there is no cooldown period
+    let safety_module = await setupContract('safety_module_contract', 'new', stablecoin1.contract.address, stablecoin1.contract.address, bob.address, 0, oneDay(), 100, null, null)
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.transfer(bob.address, 100, [])).to.eventually.be.fulfilled
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(safety_module.contract.address, 1000)).to.eventually.be.fulfilled
+    await expect(fromSigner(stablecoin1.contract, bob.address).tx.approve(safety_module.contract.address, 100)).to.eventually.be.fulfilled
+    // Alice stakes and starts the cooldown, Bob stakes without it
+    await expect(fromSigner(safety_module.contract, alice.address).tx.stake(1000)).to.eventually.be.fulfilled
+    await expect(fromSigner(safety_module.contract, alice.address).tx.cooldown()).to.eventually.be.fulfilled
+    await expect(fromSigner(safety_module.contract, bob.address).tx.stake(100)).to.eventually.be.fulfilled
+    // Bob sends Alice 1 share, her cooldown keeps running
+    await expect(fromSigner(safety_module.contract, bob.address).tx.transfer(alice.address, 1, [])).to.eventually.be.fulfilled
+    expect((await safety_module.query.getCooldownStart(alice.address)).output.isSome).to.equal(true)
+    await expect(fromSigner(safety_module.contract, alice.address).tx.unstake(1000)).to.eventually.be.fulfilled
+  })
+
+  it('Shutdown - collateral of other markets is not redeemed', async () => {
+    const {lending_pool_manager, stablecoin1, alice, stablecoin2, bob, charlie, dave, eve} = await setup()
+    // Alice lends 10000 stablecoin1, Bob lends 1000 stablecoin2
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin1.contract.address)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setAssetAllowance(stablecoin2.contract.address)).to.eventually.be.fulfilled
+    await expect(fromSigner(stablecoin1.contract, alice.address).tx.approve(lending_pool_manager.contract.address, 10000)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, alice.address).tx.lend(stablecoin1.contract.address, 10000)).to.eventually.be.fulfilled
+    await expect(fromSigner(stablecoin2.contract, bob.address).tx.approve(lending_pool_manager.contract.address, 6000)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.lend(stablecoin2.contract.address, 1000)).to.eventually.be.fulfilled
+    let btoken2_address = (await lending_pool_manager.query.getBtokenFromAsset(stablecoin2.contract.address)).output.asOk.toString();
+
+    // Bob borrows stablecoin1 with 5000 stablecoin2, the collateral is in the contract next to the stablecoin2 pool
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.setCollateralAllowance(stablecoin2.contract.address)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin1.contract.address, stablecoin2.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(lending_pool_manager.tx.setConversionRate(stablecoin2.contract.address, stablecoin1.contract.address, 1)).to.eventually.be.fulfilled
+    await expect(fromSigner(lending_pool_manager.contract, bob.address).tx.borrow(stablecoin1.contract.address, stablecoin2.contract.address, 5000)).to.eventually.be.fulfilled
+
+    // admin shuts the protocol down, the stablecoin2 market has nothing lended
+    await expect(fromSigner(lending_pool_manager.contract, charlie.address).tx.shutdown()).to.eventually.be.fulfilled
+    // btokens of the stablecoin2 market redeem only its own liquidity
+    let shares = (await lending_pool_manager.query.getSettlementShare(btoken2_address, 1000)).output.asOk
+    expect(shares[0][1].toNumber()).to.equal(1000)
+    // Bob settles the loan and gets back the collateral not seized by the stablecoin1 market
+    await expect(fromSigner(lending_pool_manager.contract, dave.address).tx.settleLoan({u8: 2})).to.eventually.be.fulfilled
+    shares = (await lending_pool_manager.query.getSettlementShare(btoken2_address, 1000)).output.asOk
+    expect(shares[0][1].toNumber()).to.equal(1000)
+  })
 })